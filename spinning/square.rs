@@ -20,7 +20,7 @@ impl App {
 
         let square = rectangle::square(0.0, 0.0, 50.0);
         let rotation = self.rotation;
-        let (x,y) = (args.width / 2.0 + self.position.0, args.height / 2.0 + self.position.1);
+        let (x,y) = (args.window_size[0] / 2.0 + self.position.0, args.window_size[1] / 2.0 + self.position.1);
 
         self.gl.draw(args.viewport(), |c , gl| {
             clear(GREEN, gl);
@@ -71,7 +71,7 @@ fn main() {
         "spinning-square",
         [200, 200]
         )
-        .opengl(opengl)
+        .graphics_api(opengl)
         .exit_on_esc(true)
         .build()
         .unwrap();
@@ -105,11 +105,8 @@ fn main() {
             }
         }
 
-        if let Some(r) = e.release_args() {
-            match r {
-                Button::Keyboard(keyboard::Key::Left) => app.stop_going_back(),
-                _ => (),
-            }
+        if let Some(Button::Keyboard(keyboard::Key::Left)) = e.release_args() {
+            app.stop_going_back();
         }
     }
 }
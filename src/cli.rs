@@ -0,0 +1,113 @@
+//! Command line handling shared by the front-ends.
+
+use crate::core::level::Levels;
+use crate::core::map::{LoadError, Map};
+use crate::core::{Pacman, Rules};
+use std::env;
+use std::iter::Skip;
+use std::process;
+use std::str::FromStr;
+
+/// What every front-end's command line has in common.
+#[derive(Debug, Default)]
+pub struct Args {
+    pub maze: Option<String>,
+    pub levels: Option<String>,
+    pub seed: Option<u64>,
+    pub rules: Rules,
+}
+
+impl Args {
+    /// Parses the command line. Flags the front-end handles itself are passed
+    /// to `extra`, which returns false for the ones it doesn't know.
+    pub fn parse(usage: &'static str, mut extra: impl FnMut(&str, &mut Argv) -> bool) -> Self {
+        let mut argv = Argv {
+            usage,
+            args: env::args().skip(1),
+        };
+        let mut args = Args::default();
+        while let Some(arg) = argv.args.next() {
+            match arg.as_str() {
+                "--seed" => args.seed = Some(argv.parsed()),
+                "--tick-rate" => match argv.parsed() {
+                    0 => argv.usage(),
+                    rate => args.rules.tick_rate = rate,
+                },
+                "--simple-ghosts" => args.rules.arcade_ghosts = false,
+                "--extra-life" => args.rules.extra_life = argv.parsed(),
+                "--extra-life-every" => args.rules.extra_life_every = argv.parsed(),
+                "--levels" => args.levels = Some(argv.value()),
+                "-h" | "--help" => argv.usage(),
+                _ if args.maze.is_none() && !arg.starts_with('-') => args.maze = Some(arg),
+                flag => {
+                    if !extra(flag, &mut argv) {
+                        argv.usage()
+                    }
+                }
+            }
+        }
+        args
+    }
+
+    /// The game asked for, with a random seed if none was given. Exits if the
+    /// maze or the levels can't be played.
+    pub fn game(&self) -> Pacman {
+        let map = self.maze.as_deref().map(load_map).unwrap_or_default();
+        let levels = self.levels.as_deref().map(load_levels).unwrap_or_default();
+        let seed = self.seed.unwrap_or_else(rand::random);
+        Pacman::with_levels(map, seed, self.rules, levels)
+    }
+}
+
+/// The arguments left to parse, anything unexpected exits with the usage.
+pub struct Argv {
+    usage: &'static str,
+    args: Skip<env::Args>,
+}
+
+impl Argv {
+    /// The value following a flag.
+    pub fn value(&mut self) -> String {
+        match self.args.next() {
+            Some(value) => value,
+            None => self.usage(),
+        }
+    }
+
+    /// The value following a flag, parsed.
+    pub fn parsed<T: FromStr>(&mut self) -> T {
+        self.value().parse().unwrap_or_else(|_| self.usage())
+    }
+
+    pub fn usage(&self) -> ! {
+        usage(self.usage)
+    }
+}
+
+pub fn usage(usage: &str) -> ! {
+    eprintln!("{}", usage);
+    process::exit(1)
+}
+
+/// Loads the maze at `path`, exiting with everything wrong with it if it can't
+/// be played.
+fn load_map(path: &str) -> Map {
+    Map::load(path).unwrap_or_else(|e| {
+        match e {
+            LoadError::Io(e) => eprintln!("{}: {}", path, e),
+            LoadError::Invalid(errors) => {
+                for e in errors {
+                    eprintln!("{}: {}", path, e);
+                }
+            }
+        }
+        process::exit(1)
+    })
+}
+
+fn load_levels(path: &str) -> Levels {
+    Levels::load(path).unwrap_or_else(|e| {
+        eprintln!("{}: {}", path, e);
+        process::exit(1)
+    })
+}
@@ -0,0 +1,56 @@
+//! Fixed timestep bookkeeping for front-ends, independent of how often they
+//! get to run.
+
+/// Turns the variable time between frames into a whole number of ticks at a
+/// steady rate. Time left over carries to the next frame, see [`FixedStep::alpha`].
+#[derive(Clone, Debug)]
+pub struct FixedStep {
+    tick: f64,
+    max_steps: u32,
+    accumulator: f64,
+}
+
+impl FixedStep {
+    /// `rate` ticks per second, running at most `max_steps` ticks per frame so
+    /// a long stall doesn't make the game race to catch up.
+    pub fn new(rate: f64, max_steps: u32) -> Self {
+        assert!(rate > 0.0, "tick rate must be positive");
+        FixedStep {
+            tick: 1.0 / rate,
+            max_steps,
+            accumulator: 0.0,
+        }
+    }
+
+    /// Seconds per tick.
+    pub fn tick(&self) -> f64 {
+        self.tick
+    }
+
+    /// Adds `dt` seconds and returns how many ticks are due. Time beyond
+    /// `max_steps` ticks is dropped.
+    pub fn advance(&mut self, dt: f64) -> u32 {
+        self.accumulator += dt;
+        let mut steps = 0;
+        while self.accumulator >= self.tick {
+            if steps == self.max_steps {
+                self.accumulator %= self.tick;
+                break;
+            }
+            self.accumulator -= self.tick;
+            steps += 1;
+        }
+        steps
+    }
+
+    /// How far along the way to the next tick we are, from 0 to 1. Renderers
+    /// can use it to interpolate between the last two ticks.
+    pub fn alpha(&self) -> f64 {
+        (self.accumulator / self.tick).min(1.0)
+    }
+
+    /// Forgets any time accumulated so far.
+    pub fn reset(&mut self) {
+        self.accumulator = 0.0;
+    }
+}
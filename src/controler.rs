@@ -1,48 +1,157 @@
-use crate::pacman::{ghost::Ghost, ghost::GhostMode, map::Map, Direction, Pacman, Stats};
 use piston::input::Button;
 use piston::input::Event;
 use piston::input::{PressEvent, UpdateEvent};
+use rust_gaem::clock::FixedStep;
+use rust_gaem::core::fruit::Fruit;
+use rust_gaem::core::replay::Replay;
+use rust_gaem::core::scores::{self, HighScore, HighScores, Initials};
+use rust_gaem::core::Event as GameEvent;
+use rust_gaem::core::{ghost::Ghost, map::Map, Direction, GamePhase, Pacman, Stats};
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+const FAST_FORWARD: f64 = 4.0;
+/// Most ticks run per update, a slower machine plays in slow motion rather
+/// than stuttering.
+const MAX_STEPS: u32 = 4 * FAST_FORWARD as u32;
+const QUICK_SAVE: &str = "quicksave.sav";
+const HIGH_SCORES: &str = "highscores.txt";
 
 pub struct Controler {
     game: Pacman,
-    delta: f64,
+    clock: FixedStep,
+    previous: Snapshot,
+    popups: Vec<Popup>,
+    /// Ticks left blinking the newest life.
+    extra_life: u32,
     paused: bool,
+    recording: Option<Replay>,
+    playback: Option<Playback>,
+    high_scores: HighScores,
+    /// The maze's name in the high score table.
+    maze: String,
+    /// Initials being entered for a new high score.
+    initials: Option<Initials>,
+    /// Where the last game went in the high score table.
+    new_high_score: Option<usize>,
+}
+
+/// Where things were before the last tick, for the view to interpolate from.
+struct Snapshot {
+    player: (f64, f64),
+    ghosts: Vec<(f64, f64)>,
+}
+
+impl Snapshot {
+    fn of(game: &Pacman) -> Self {
+        Snapshot {
+            player: game.player_position(),
+            ghosts: game.ghosts().iter().map(Ghost::position).collect(),
+        }
+    }
+}
+
+/// Points scored, shown for a moment where they were scored.
+pub struct Popup {
+    pub pos: (i32, i32),
+    pub points: u32,
+    ticks: u32,
+}
+
+struct Playback {
+    replay: Replay,
+    next: usize,
+    fast_forward: bool,
 }
 
 impl Controler {
-    pub fn new(game: Pacman) -> Self {
+    /// Plays `game`, which is on the maze loaded from `maze`, the built-in one
+    /// if there's none.
+    pub fn new(game: Pacman, maze: Option<&str>) -> Self {
+        let high_scores = HighScores::load(HIGH_SCORES).unwrap_or_else(|e| {
+            eprintln!("Failed to load {}: {}", HIGH_SCORES, e);
+            HighScores::default()
+        });
         Controler {
+            previous: Snapshot::of(&game),
+            clock: FixedStep::new(game.tick_rate() as f64, MAX_STEPS),
+            popups: vec![],
+            extra_life: 0,
             game,
-            delta: 0.0,
             paused: false,
+            recording: None,
+            playback: None,
+            high_scores,
+            maze: scores::maze_name(maze),
+            initials: None,
+            new_high_score: None,
+        }
+    }
+
+    /// Records every input, see `save_recording`.
+    pub fn recording(game: Pacman, maze: Option<&str>) -> Self {
+        let replay = Replay::new(&game);
+        Controler {
+            recording: Some(replay),
+            ..Controler::new(game, maze)
+        }
+    }
+
+    /// Plays back `replay`, ignoring the player's input. It never makes the
+    /// high scores, so which maze it's on doesn't matter.
+    pub fn replaying(replay: Replay) -> Self {
+        let game = replay.game();
+        Controler {
+            playback: Some(Playback {
+                replay,
+                next: 0,
+                fast_forward: false,
+            }),
+            ..Controler::new(game, None)
         }
     }
 
     pub fn event(&mut self, event: &Event) -> bool {
-        if let Some(k) = event.press_args() {
+        if let Some(k) = event.press_args().filter(|_| self.initials.is_some()) {
+            self.type_initial(k);
+        } else if let Some(k) = event.press_args() {
             use piston::input::keyboard::Key;
             match k {
-                Button::Keyboard(Key::Up) => self.game.set_direction_intent(Direction::Up),
-                Button::Keyboard(Key::Down) => self.game.set_direction_intent(Direction::Down),
-                Button::Keyboard(Key::Left) => self.game.set_direction_intent(Direction::Left),
-                Button::Keyboard(Key::Right) => self.game.set_direction_intent(Direction::Right),
+                Button::Keyboard(Key::Up) => self.steer(Direction::Up),
+                Button::Keyboard(Key::Down) => self.steer(Direction::Down),
+                Button::Keyboard(Key::Left) => self.steer(Direction::Left),
+                Button::Keyboard(Key::Right) => self.steer(Direction::Right),
                 Button::Keyboard(Key::Q) => return true,
-                Button::Keyboard(Key::K) => self.game.set_direction_intent(Direction::Up),
-                Button::Keyboard(Key::J) => self.game.set_direction_intent(Direction::Down),
-                Button::Keyboard(Key::H) => self.game.set_direction_intent(Direction::Left),
-                Button::Keyboard(Key::L) => self.game.set_direction_intent(Direction::Right),
+                Button::Keyboard(Key::K) => self.steer(Direction::Up),
+                Button::Keyboard(Key::J) => self.steer(Direction::Down),
+                Button::Keyboard(Key::H) => self.steer(Direction::Left),
+                Button::Keyboard(Key::L) => self.steer(Direction::Right),
                 Button::Keyboard(Key::P) => self.paused = !self.paused,
+                Button::Keyboard(Key::F5) => self.quick_save(),
+                Button::Keyboard(Key::F9) => self.quick_load(),
+                Button::Keyboard(Key::Return) | Button::Keyboard(Key::Space) => self.restart(),
+                Button::Keyboard(Key::F) => {
+                    if let Some(p) = &mut self.playback {
+                        p.fast_forward = !p.fast_forward;
+                    }
+                }
+                Button::Keyboard(Key::N) if self.paused && self.playback.is_some() => {
+                    self.advance()
+                }
                 // Button::Keyboard(Key::U) => self.game.level_up(),
                 _ => (),
             }
         }
 
         if let Some(u) = event.update_args() {
-            self.delta += u.dt;
-            if self.delta > 0.25 {
-                self.delta -= 0.25;
-                if !self.paused {
-                    self.game.tick();
+            if !self.paused {
+                let dt = match &self.playback {
+                    Some(p) if p.fast_forward => u.dt * FAST_FORWARD,
+                    _ => u.dt,
+                };
+                for _ in 0..self.clock.advance(dt) {
+                    self.advance();
                 }
             }
         }
@@ -50,10 +159,165 @@ impl Controler {
         false
     }
 
+    fn steer(&mut self, direction: Direction) {
+        if self.playback.is_some() {
+            return;
+        }
+        if let Some(r) = &mut self.recording {
+            r.record(self.game.ticks(), direction);
+        }
+        self.game.set_direction_intent(direction);
+    }
+
+    fn advance(&mut self) {
+        if let Some(p) = &mut self.playback {
+            let inputs = p.replay.inputs();
+            while let Some(&(tick, direction)) = inputs.get(p.next) {
+                if tick > self.game.ticks() {
+                    break;
+                }
+                self.game.set_direction_intent(direction);
+                p.next += 1;
+            }
+        }
+        self.previous = Snapshot::of(&self.game);
+        let phase = self.game.phase();
+        self.game.tick();
+        if phase != GamePhase::GameOver && self.game.phase() == GamePhase::GameOver {
+            self.game_over();
+        }
+        self.popups.retain(|p| p.ticks > 1);
+        for p in self.popups.iter_mut() {
+            p.ticks -= 1;
+        }
+        self.extra_life = self.extra_life.saturating_sub(1);
+        for event in self.game.take_events() {
+            match event {
+                GameEvent::GhostEaten { pos, points, .. }
+                | GameEvent::FruitEaten { pos, points, .. } => self.popups.push(Popup {
+                    pos,
+                    points,
+                    // shown for a second
+                    ticks: self.game.tick_rate(),
+                }),
+                // blinks for two seconds
+                GameEvent::ExtraLife { .. } => self.extra_life = 2 * self.game.tick_rate(),
+                GameEvent::AllGhostsEaten { .. } => (),
+            }
+        }
+    }
+
+    /// Asks for the player's initials if they made it into the high scores.
+    fn game_over(&mut self) {
+        if self.playback.is_none() && self.high_scores.qualifies(self.game.stats().score) {
+            self.initials = Some(Initials::default());
+        }
+    }
+
+    fn type_initial(&mut self, button: Button) {
+        use piston::input::keyboard::Key;
+        let initials = match &mut self.initials {
+            Some(initials) => initials,
+            None => return,
+        };
+        match button {
+            Button::Keyboard(Key::Up) => initials.cycle(1),
+            Button::Keyboard(Key::Down) => initials.cycle(-1),
+            Button::Keyboard(Key::Right) | Button::Keyboard(Key::Return) => initials.take(),
+            Button::Keyboard(Key::Left) | Button::Keyboard(Key::Backspace) => initials.back(),
+            Button::Keyboard(k) => {
+                if let Some(c) = char::from_u32(k.into()) {
+                    initials.type_letter(c);
+                }
+            }
+            _ => (),
+        }
+        if initials.is_done() {
+            let score = HighScore::new(
+                initials.taken(),
+                self.game.stats(),
+                self.game.seed(),
+                &self.maze,
+            );
+            self.initials = None;
+            self.new_high_score = self.high_scores.insert(score);
+            if let Err(e) = self.high_scores.save(HIGH_SCORES) {
+                eprintln!("Failed to save to {}: {}", HIGH_SCORES, e);
+            }
+        }
+    }
+
+    /// Starts over once the game is over. A recording starts over too, it only
+    /// ever holds the last game.
+    fn restart(&mut self) {
+        if self.game.phase() != GamePhase::GameOver || self.playback.is_some() {
+            return;
+        }
+        self.game.restart();
+        if let Some(r) = &mut self.recording {
+            *r = Replay::new(&self.game);
+        }
+        self.previous = Snapshot::of(&self.game);
+        self.popups.clear();
+        self.extra_life = 0;
+        self.new_high_score = None;
+    }
+
+    fn quick_save(&self) {
+        if let Err(e) = File::create(QUICK_SAVE).and_then(|f| self.game.save(f)) {
+            eprintln!("Failed to save to {}: {}", QUICK_SAVE, e);
+        }
+    }
+
+    fn quick_load(&mut self) {
+        if self.recording.is_some() || self.playback.is_some() {
+            eprintln!("Can't load a save while recording or watching a replay");
+            return;
+        }
+        match File::open(QUICK_SAVE)
+            .map_err(Into::into)
+            .and_then(Pacman::load)
+        {
+            Ok(game) => {
+                self.previous = Snapshot::of(&game);
+                self.popups.clear();
+                self.extra_life = 0;
+                self.game = game;
+            }
+            Err(e) => eprintln!("Failed to load {}: {}", QUICK_SAVE, e),
+        }
+    }
+
+    pub fn save_recording<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        match &self.recording {
+            Some(r) => r.save(path),
+            None => Ok(()),
+        }
+    }
+
+    /// How far the game is between the last tick and the next, from 0 to 1.
+    pub fn tick_progress(&self) -> f64 {
+        self.clock.alpha()
+    }
+
     pub fn get_player(&self) -> (i32, i32, Direction) {
         self.game.player()
     }
 
+    pub fn get_player_position(&self) -> (f64, f64) {
+        self.game.player_position()
+    }
+
+    /// The player's position before the last tick.
+    pub fn get_previous_player_position(&self) -> (f64, f64) {
+        self.previous.player
+    }
+
+    /// Each ghost's position before the last tick.
+    pub fn get_previous_ghost_positions(&self) -> &[(f64, f64)] {
+        &self.previous.ghosts
+    }
+
     pub fn get_map(&self) -> &Map {
         self.game.map()
     }
@@ -62,19 +326,67 @@ impl Controler {
         self.game.ghosts()
     }
 
-    pub fn frightened(&self) -> bool {
-        self.game.ghost_mode() == GhostMode::Frightened
+    pub fn get_popups(&self) -> &[Popup] {
+        &self.popups
+    }
+
+    /// The bonus fruit of `level`, counting from 1.
+    pub fn get_level_fruit(&self, level: usize) -> Fruit {
+        self.game.levels().get(level).fruit
+    }
+
+    pub fn get_fruit(&self) -> Option<(Fruit, (i32, i32))> {
+        self.game.fruit()
+    }
+
+    /// Whether frightened ghosts should be drawn white.
+    pub fn fright_flash(&self) -> bool {
+        self.game.fright_flash()
     }
 
     pub fn get_stats(&self) -> Stats {
         self.game.stats()
     }
+
+    /// Whether to leave the newest life out of the HUD, it blinks for a moment
+    /// after an extra life.
+    pub fn new_life_blink(&self) -> bool {
+        // a quarter of a second on, then off
+        self.extra_life / (self.game.tick_rate() / 4).max(1) % 2 == 1
+    }
+
+    pub fn get_high_scores(&self) -> &HighScores {
+        &self.high_scores
+    }
+
+    /// Where the last game went in the high score table, if it made it.
+    pub fn get_new_high_score(&self) -> Option<usize> {
+        self.new_high_score
+    }
+
+    /// The initials being entered for a new high score.
+    pub fn get_initials(&self) -> Option<&Initials> {
+        self.initials.as_ref()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn get_phase(&self) -> GamePhase {
+        self.game.phase()
+    }
+
+    /// How far into the current phase the game is, from 0 to 1.
+    pub fn phase_progress(&self) -> f64 {
+        self.game.phase_progress()
+    }
 }
 
 // DEBUG VIEWS
 #[allow(dead_code)]
 impl Controler {
-    pub fn ghost_targets(&self) -> [(i32, i32); 4] {
+    pub fn ghost_targets(&self) -> Vec<(i32, i32)> {
         self.game.ghost_targets()
     }
 }
@@ -0,0 +1,767 @@
+pub mod fruit;
+pub mod ghost;
+pub mod level;
+pub mod map;
+mod motion;
+pub mod replay;
+pub mod save;
+pub mod scores;
+mod speed;
+
+use self::map::Map;
+use self::map::Tile;
+use self::map::PU;
+
+use self::fruit::{Fruit, FRUIT_PELLETS, FRUIT_TIME};
+use self::ghost::brain::GhostBrain;
+use self::ghost::{Ghost, GhostMode, Ghosts, Interaction, NextOut};
+use self::motion::Motion;
+use self::save::{Reader, SaveError, Writer};
+use self::level::{Level, Levels};
+use self::speed::units;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::fmt;
+use std::io::{Read, Write};
+use std::str::FromStr;
+
+pub use self::motion::SUBTILE;
+
+/// The game is meant to be ticked this many times per second, unless it was
+/// made with another rate, see `Rules::tick_rate`.
+pub const TICKS_PER_SECOND: u32 = 60;
+
+/// Ticks in `seconds` at `rate` ticks per second, at least one for any wait at
+/// all.
+pub(crate) fn ticks(seconds: f64, rate: u32) -> u32 {
+    if seconds <= 0.0 {
+        0
+    } else {
+        ((seconds * rate as f64).round() as u32).max(1)
+    }
+}
+
+const SCORE_PELLET: u32 = 10;
+const SCORE_PU: u32 = 50;
+/// Points for the first ghost eaten on a power up, each next one doubles it.
+const SCORE_GHOST: u32 = 200;
+/// Extra points for eating all four ghosts on a single power up, none in the
+/// arcade.
+const SCORE_ALL_GHOSTS: u32 = 0;
+/// Seconds "READY!" shows before play starts, and resumes after a death.
+const READY_TIME: f64 = 2.0;
+const DYING_TIME: f64 = 2.0;
+/// Seconds the cleared maze shows before the next level.
+const LEVEL_COMPLETE_TIME: f64 = 2.0;
+
+pub struct Pacman {
+    map: Map,
+    lives: u8,
+    score: u32,
+    level: usize,
+    motion: Motion,
+    direction: Direction,
+    direction_intent: Direction,
+    ghosts: Ghosts,
+    phase: GamePhase,
+    /// Ticks left in the current phase, 0 for phases that don't end by
+    /// themselves.
+    phase_timer: u32,
+    /// Ticks until the bonus fruit goes away, 0 when there's none.
+    fruit_timer: u32,
+    ticks: u32,
+    seed: u64,
+    rng: ChaCha8Rng,
+    events: Vec<Event>,
+    rules: Rules,
+    levels: Levels,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub fn to_vector(self) -> (i32, i32) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+        }
+    }
+
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Direction::Up => "up",
+            Direction::Down => "down",
+            Direction::Left => "left",
+            Direction::Right => "right",
+        })
+    }
+}
+
+impl FromStr for Direction {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "up" => Ok(Direction::Up),
+            "down" => Ok(Direction::Down),
+            "left" => Ok(Direction::Left),
+            "right" => Ok(Direction::Right),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Where the game is at, see `Pacman::phase`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GamePhase {
+    /// "READY!", before play starts or resumes after a death.
+    Ready,
+    Playing,
+    /// The player was caught, everything stands still while they die.
+    Dying,
+    /// Every pellet was eaten, the next level starts shortly.
+    LevelComplete,
+    /// No lives left, until `Pacman::restart`.
+    GameOver,
+}
+
+impl fmt::Display for GamePhase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            GamePhase::Ready => "ready",
+            GamePhase::Playing => "playing",
+            GamePhase::Dying => "dying",
+            GamePhase::LevelComplete => "level-complete",
+            GamePhase::GameOver => "game-over",
+        })
+    }
+}
+
+impl FromStr for GamePhase {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "ready" => Ok(GamePhase::Ready),
+            "playing" => Ok(GamePhase::Playing),
+            "dying" => Ok(GamePhase::Dying),
+            "level-complete" => Ok(GamePhase::LevelComplete),
+            "game-over" => Ok(GamePhase::GameOver),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Things that happened during a tick, for front-ends to show, see
+/// `Pacman::take_events`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// A ghost was eaten at `pos`, the `chain`th on the current power up.
+    GhostEaten {
+        pos: (i32, i32),
+        chain: u8,
+        points: u32,
+    },
+    /// Every ghost was eaten on a single power up, worth `points` on top
+    /// of the ghosts themselves (`total`).
+    AllGhostsEaten { total: u32, points: u32 },
+    /// The score went past an extra life's threshold, the player now has
+    /// `lives`.
+    ExtraLife { lives: u8 },
+    FruitEaten {
+        pos: (i32, i32),
+        fruit: Fruit,
+        points: u32,
+    },
+}
+
+/// Options changing how the game plays.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Rules {
+    /// Ticks per second the game is meant to run at. Speeds and timers are
+    /// scaled to match, so it plays at the same pace in finer or coarser steps.
+    pub tick_rate: u32,
+    /// Ghosts break ties between equally good turns in the order up, left,
+    /// down, right, turn around whenever their mode changes and can't turn up
+    /// just above the house and the player's start, as in the arcade.
+    pub arcade_ghosts: bool,
+    /// Score the first extra life comes at, 0 for none.
+    pub extra_life: u32,
+    /// Points between extra lives after the first one, 0 for only the one.
+    pub extra_life_every: u32,
+    /// Extra lives past this many lives are lost.
+    pub max_lives: u8,
+}
+
+impl Rules {
+    /// How many extra lives `score` is worth.
+    fn extra_lives(&self, score: u32) -> u32 {
+        match self.extra_life {
+            0 => 0,
+            first if score < first => 0,
+            first => match self.extra_life_every {
+                0 => 1,
+                every => 1 + (score - first) / every,
+            },
+        }
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            tick_rate: TICKS_PER_SECOND,
+            arcade_ghosts: true,
+            extra_life: 10_000,
+            extra_life_every: 0,
+            max_lives: 6,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Stats {
+    pub lives: u8,
+    pub score: u32,
+    pub level: usize,
+}
+
+impl Pacman {
+    pub fn new() -> Self {
+        Pacman::default()
+    }
+
+    /// A game on `map` where every random decision is drawn from `seed`, the same
+    /// seed and input always play out the same game.
+    pub fn with_map(map: Map, seed: u64) -> Self {
+        Pacman::with_rules(map, seed, Rules::default())
+    }
+
+    /// A game played by `rules`.
+    ///
+    /// # Panics
+    ///
+    /// If `rules.tick_rate` is 0.
+    pub fn with_rules(map: Map, seed: u64, rules: Rules) -> Self {
+        Pacman::with_levels(map, seed, rules, Levels::default())
+    }
+
+    /// A game played through `levels` instead of the arcade's.
+    ///
+    /// # Panics
+    ///
+    /// If `rules.tick_rate` is 0.
+    pub fn with_levels(map: Map, seed: u64, rules: Rules, levels: Levels) -> Self {
+        assert!(rules.tick_rate > 0, "tick rate must be positive");
+        Pacman {
+            lives: 5,
+            score: 0,
+            level: 1,
+            motion: Motion::at(map.player_start()),
+            direction: Direction::Left,
+            direction_intent: Direction::Left,
+            ghosts: Ghosts::new(&map, rules, levels.get(1)),
+            phase: GamePhase::Ready,
+            phase_timer: ticks(READY_TIME, rules.tick_rate),
+            fruit_timer: 0,
+            ticks: 0,
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            events: vec![],
+            rules,
+            levels,
+            map,
+        }
+    }
+
+    /// Starts a new game on the same maze, with the same seed, rules, levels and
+    /// brains.
+    pub fn restart(&mut self) {
+        let mut map = std::mem::take(&mut self.map);
+        map.reset();
+        let levels = std::mem::take(&mut self.levels);
+        let mut game = Pacman::with_levels(map, self.seed, self.rules, levels);
+        std::mem::swap(&mut game.ghosts, &mut self.ghosts);
+        game.ghosts.reset(&game.map, game.levels.get(1));
+        *self = game;
+    }
+
+    /// Steers the player. Turning back is immediate, other turns are taken at the
+    /// next tile center with no wall in the way.
+    pub fn set_direction_intent(&mut self, direction: Direction) {
+        if !matches!(self.phase, GamePhase::Ready | GamePhase::Playing) {
+            return;
+        }
+        self.direction_intent = direction;
+        if self.motion.direction() == Some(direction.opposite()) {
+            self.motion.reverse();
+            self.direction = direction;
+        }
+    }
+
+    /// Advances the game by one step.
+    pub fn tick(&mut self) {
+        self.ticks += 1;
+        self.phase_timer = self.phase_timer.saturating_sub(1);
+        match self.phase {
+            GamePhase::Playing => self.play(),
+            GamePhase::GameOver => (),
+            _ if self.phase_timer > 0 => (),
+            GamePhase::Ready => self.phase = GamePhase::Playing,
+            GamePhase::Dying if self.lives == 0 => self.phase = GamePhase::GameOver,
+            GamePhase::Dying => {
+                self.motion = Motion::at(self.map.player_start());
+                self.ghosts
+                    .lose_life(&self.map, self.levels.get(self.level));
+                self.set_phase(GamePhase::Ready, READY_TIME);
+            }
+            GamePhase::LevelComplete => {
+                self.advance_level();
+                self.set_phase(GamePhase::Ready, READY_TIME);
+            }
+        }
+    }
+
+    fn set_phase(&mut self, phase: GamePhase, seconds: f64) {
+        self.phase = phase;
+        self.phase_timer = ticks(seconds, self.rules.tick_rate);
+    }
+
+    fn play(&mut self) {
+        let prev_tile = self.motion.tile();
+        self.fruit_timer = self.fruit_timer.saturating_sub(1);
+        self.move_pacman();
+        if self.map.pellets() == 0 {
+            self.fruit_timer = 0;
+            self.set_phase(GamePhase::LevelComplete, LEVEL_COMPLETE_TIME);
+            return;
+        }
+        self.move_ghosts();
+        match self
+            .ghosts
+            .interact_with_player(self.motion.tile(), prev_tile)
+        {
+            Some(Interaction::KillPlayer) => {
+                self.fruit_timer = 0;
+                self.lives -= 1;
+                self.set_phase(GamePhase::Dying, DYING_TIME);
+            }
+            Some(Interaction::KillGhosts(killed)) => {
+                for (pos, chain) in killed {
+                    let points = SCORE_GHOST << (chain - 1).min(3);
+                    self.add_score(points);
+                    self.events.push(Event::GhostEaten { pos, chain, points });
+                    if chain as usize == self.ghosts.get().len() {
+                        self.add_score(SCORE_ALL_GHOSTS);
+                        self.events.push(Event::AllGhostsEaten {
+                            total: (0..chain).map(|i| SCORE_GHOST << i.min(3)).sum(),
+                            points: SCORE_ALL_GHOSTS,
+                        });
+                    }
+                }
+            }
+            None => (),
+        }
+    }
+
+    fn move_pacman(&mut self) {
+        let speeds = self.current_level().speeds;
+        let frightened = self.ghosts.ghost_mode() == GhostMode::Frightened;
+        let eating = matches!(
+            self.map.get(self.motion.to.0, self.motion.to.1),
+            Some(Tile::NotWall(PU::Dot)) | Some(Tile::NotWall(PU::PowerUp))
+        );
+        let speed = match (frightened, eating) {
+            (false, false) => speeds.pacman,
+            (false, true) => speeds.pacman_dots,
+            (true, false) => speeds.pacman_fright,
+            (true, true) => speeds.pacman_fright_dots,
+        };
+        let mut budget = units(speed, self.rules.tick_rate);
+        loop {
+            if self.motion.at_center() {
+                if self.can_go(self.direction_intent) {
+                    self.direction = self.direction_intent;
+                }
+                if self.can_go(self.direction) {
+                    let next = self.next_tile(self.direction);
+                    self.motion.head_to(next);
+                } else {
+                    self.motion.stop();
+                    break;
+                }
+            }
+            match self.motion.step(budget) {
+                Some(left) => budget = left,
+                None => break,
+            }
+        }
+
+        let (x, y) = self.motion.tile();
+        match self.map.get(x, y) {
+            Some(Tile::NotWall(PU::Dot)) => {
+                self.map.consume(x, y);
+                self.add_score(SCORE_PELLET);
+                self.pellet_eaten();
+            }
+            Some(Tile::NotWall(PU::PowerUp)) => {
+                self.map.consume(x, y);
+                self.ghosts.frighten(self.levels.get(self.level));
+                self.add_score(SCORE_PU);
+                self.pellet_eaten();
+            }
+            _ => (),
+        }
+        if let Some((fruit, pos)) = self.fruit() {
+            if pos == (x, y) {
+                self.add_score(fruit.points());
+                self.fruit_timer = 0;
+                self.events.push(Event::FruitEaten {
+                    pos,
+                    fruit,
+                    points: fruit.points(),
+                });
+            }
+        }
+    }
+
+    fn add_score(&mut self, points: u32) {
+        let before = self.rules.extra_lives(self.score);
+        self.score += points;
+        for _ in before..self.rules.extra_lives(self.score) {
+            if self.lives < self.rules.max_lives {
+                self.lives += 1;
+                self.events.push(Event::ExtraLife { lives: self.lives });
+            }
+        }
+    }
+
+    /// Called after each dot or power up. `Map::eaten` counts both, one at a
+    /// time, so each count in `FRUIT_PELLETS` brings out the fruit once.
+    fn pellet_eaten(&mut self) {
+        self.ghosts.dot_eaten();
+        if FRUIT_PELLETS.contains(&self.map.eaten()) && self.map.fruit_spot().is_some() {
+            self.fruit_timer = ticks(FRUIT_TIME, self.rules.tick_rate);
+        }
+    }
+
+    fn move_ghosts(&mut self) {
+        let (x, y) = self.motion.tile();
+        self.ghosts.move_ghosts(
+            &self.map,
+            (x, y, self.direction),
+            self.levels.get(self.level),
+            &mut self.rng,
+        );
+    }
+
+    fn next_tile(&self, direction: Direction) -> (i32, i32) {
+        let (x, y) = self.motion.from;
+        let (dx, dy) = direction.to_vector();
+        self.map.wrap(x + dx, y + dy)
+    }
+
+    fn can_go(&self, direction: Direction) -> bool {
+        let (x, y) = self.next_tile(direction);
+        matches!(self.map.get(x, y), Some(Tile::NotWall(_)))
+    }
+
+    fn advance_level(&mut self) {
+        self.level += 1;
+        self.motion = Motion::at(self.map.player_start());
+        self.fruit_timer = 0;
+        self.ghosts.reset(&self.map, self.levels.get(self.level));
+        self.map.reset();
+    }
+
+    /// Everything that happened since the last call.
+    pub fn take_events(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub fn map(&self) -> &Map {
+        &self.map
+    }
+
+    pub fn ticks(&self) -> u32 {
+        self.ticks
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Ticks per second the game is meant to run at.
+    pub fn tick_rate(&self) -> u32 {
+        self.rules.tick_rate
+    }
+
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    pub fn levels(&self) -> &Levels {
+        &self.levels
+    }
+
+    /// The parameters of the level being played.
+    pub fn current_level(&self) -> &Level {
+        self.levels.get(self.level)
+    }
+
+    /// The player's tile and the direction they want to go in.
+    pub fn player(&self) -> (i32, i32, Direction) {
+        let (x, y) = self.motion.tile();
+        (x, y, self.direction_intent)
+    }
+
+    /// The player's position in tiles, in between tiles while moving.
+    pub fn player_position(&self) -> (f64, f64) {
+        self.motion.position()
+    }
+
+    pub fn ghosts(&self) -> &[Ghost] {
+        self.ghosts.get()
+    }
+
+    /// Replaces the brain of the `i`th ghost, for the rest of the game. Brains
+    /// aren't saved, a loaded game has the classic ones.
+    ///
+    /// # Panics
+    ///
+    /// If there's no `i`th ghost, see `ghosts`.
+    pub fn set_brain(&mut self, i: usize, brain: Box<dyn GhostBrain>) {
+        self.ghosts.set_brain(i, brain);
+    }
+
+    /// The bonus fruit on the maze and where it is, if there's one.
+    pub fn fruit(&self) -> Option<(Fruit, (i32, i32))> {
+        match self.map.fruit_spot() {
+            Some(pos) if self.fruit_timer > 0 => Some((self.current_level().fruit, pos)),
+            _ => None,
+        }
+    }
+
+    pub fn phase(&self) -> GamePhase {
+        self.phase
+    }
+
+    /// How far into the current phase the game is, from 0 to 1, always 0 for
+    /// phases that don't end by themselves.
+    pub fn phase_progress(&self) -> f64 {
+        let total = match self.phase {
+            GamePhase::Ready => READY_TIME,
+            GamePhase::Dying => DYING_TIME,
+            GamePhase::LevelComplete => LEVEL_COMPLETE_TIME,
+            GamePhase::Playing | GamePhase::GameOver => return 0.0,
+        };
+        1.0 - self.phase_timer as f64 / ticks(total, self.rules.tick_rate) as f64
+    }
+
+    pub fn ghost_mode(&self) -> GhostMode {
+        self.ghosts.ghost_mode()
+    }
+
+    /// Ticks until frightened ghosts recover.
+    pub fn fright_remaining(&self) -> u32 {
+        self.ghosts.fright_remaining()
+    }
+
+    /// Whether frightened ghosts are in the white half of a flash, warning
+    /// that they're about to recover.
+    pub fn fright_flash(&self) -> bool {
+        self.ghosts.flashing(self.current_level().flashes)
+    }
+
+    /// The next ghost to leave the house and what it's waiting for.
+    pub fn next_out(&self) -> Option<NextOut> {
+        self.ghosts.next_out(self.current_level())
+    }
+
+    /// Blinky's Cruise Elroy stage, 1 or 2 once few enough pellets are left
+    /// and 0 otherwise.
+    pub fn elroy(&self) -> u8 {
+        self.ghosts.elroy(&self.map, self.current_level())
+    }
+
+    pub fn stats(&self) -> Stats {
+        Stats {
+            lives: self.lives,
+            score: self.score,
+            level: self.level,
+        }
+    }
+}
+
+// SAVES
+impl Pacman {
+    /// Writes the whole game state, it can be restored with `Pacman::load`.
+    pub fn save<W: Write>(&self, mut out: W) -> std::io::Result<()> {
+        let mut w = Writer::default();
+        w.record(save::HEADER, &[&save::VERSION]);
+        w.record("seed", &[&self.seed]);
+        w.record("tick-rate", &[&self.rules.tick_rate]);
+        w.record(
+            "rules",
+            &[
+                &self.rules.arcade_ghosts,
+                &self.rules.extra_life,
+                &self.rules.extra_life_every,
+                &self.rules.max_lives,
+            ],
+        );
+        w.record("levels", &[&self.levels.source().lines().count()]);
+        for line in self.levels.source().lines() {
+            w.raw(line);
+        }
+        let rng_seed = self
+            .rng
+            .get_seed()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>();
+        w.record(
+            "rng",
+            &[&rng_seed, &self.rng.get_stream(), &self.rng.get_word_pos()],
+        );
+        w.record("ticks", &[&self.ticks]);
+        w.record("phase", &[&self.phase, &self.phase_timer]);
+        w.record(
+            "player",
+            &[
+                &self.motion.from.0,
+                &self.motion.from.1,
+                &self.motion.to.0,
+                &self.motion.to.1,
+                &self.motion.progress,
+                &self.direction,
+                &self.direction_intent,
+            ],
+        );
+        w.record("stats", &[&self.lives, &self.score, &self.level]);
+        w.record("fruit", &[&self.fruit_timer]);
+        self.map.save(&mut w);
+        self.ghosts.save(&mut w);
+        out.write_all(w.finish().as_bytes())
+    }
+
+    pub fn load<R: Read>(mut input: R) -> Result<Self, SaveError> {
+        let mut src = String::new();
+        input.read_to_string(&mut src)?;
+        let mut r = Reader::new(&src);
+        let version = r
+            .record(save::HEADER)
+            .and_then(|mut f| f.next::<u32>())
+            .map_err(|_| SaveError::NotASave)?;
+        if version != save::VERSION {
+            return Err(SaveError::Version(version));
+        }
+        let seed = r.record("seed")?.next()?;
+        let tick_rate = r.record("tick-rate")?.next()?;
+        if tick_rate == 0 {
+            return Err(r.invalid("tick rate must be positive".to_string()));
+        }
+        let mut f = r.record("rules")?;
+        let rules = Rules {
+            tick_rate,
+            arcade_ghosts: f.next()?,
+            extra_life: f.next()?,
+            extra_life_every: f.next()?,
+            max_lives: f.next()?,
+        };
+        let lines = r.record("levels")?.next::<usize>()?;
+        let mut levels_src = String::new();
+        for _ in 0..lines {
+            levels_src.push_str(r.raw()?);
+            levels_src.push('\n');
+        }
+        let levels =
+            Levels::parse(&levels_src).map_err(|e| r.invalid(format!("bad levels: {}", e)))?;
+        let mut f = r.record("rng")?;
+        let rng_seed = f.next::<String>()?;
+        let mut seed_bytes = [0; 32];
+        for (i, b) in seed_bytes.iter_mut().enumerate() {
+            *b = rng_seed
+                .get(i * 2..i * 2 + 2)
+                .and_then(|b| u8::from_str_radix(b, 16).ok())
+                .ok_or_else(|| r.invalid("malformed rng seed".to_string()))?;
+        }
+        let mut rng = ChaCha8Rng::from_seed(seed_bytes);
+        rng.set_stream(f.next()?);
+        rng.set_word_pos(f.next()?);
+        let ticks = r.record("ticks")?.next()?;
+        let mut f = r.record("phase")?;
+        let (phase, phase_timer) = (f.next()?, f.next()?);
+        let mut f = r.record("player")?;
+        let motion = Motion {
+            from: f.pos()?,
+            to: f.pos()?,
+            progress: f.next()?,
+        };
+        let direction = f.next()?;
+        let direction_intent = f.next()?;
+        let mut f = r.record("stats")?;
+        let (lives, score, level) = (f.next()?, f.next()?, f.next()?);
+        let fruit_timer = r.record("fruit")?.next()?;
+        let map = Map::load_state(&mut r)?;
+        let ghosts = Ghosts::load_state(&mut r, &map, rules, levels.get(level))?;
+        Ok(Pacman {
+            map,
+            lives,
+            score,
+            level,
+            motion,
+            direction,
+            direction_intent,
+            ghosts,
+            phase,
+            phase_timer,
+            fruit_timer,
+            ticks,
+            seed,
+            rng,
+            events: vec![],
+            rules,
+            levels,
+        })
+    }
+}
+
+impl Default for Pacman {
+    fn default() -> Self {
+        Pacman::with_map(Map::new(), rand::random())
+    }
+}
+
+// // DEBUG VIEWS
+#[allow(dead_code)]
+impl Pacman {
+    pub fn ghost_targets(&self) -> Vec<(i32, i32)> {
+        let (x, y) = self.motion.tile();
+        self.ghosts
+            .targets(&self.map, (x, y, self.direction), self.current_level())
+    }
+
+    pub fn level_up(&mut self) {
+        self.map.remove_all_pellets();
+    }
+}
@@ -0,0 +1,67 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// How long bonus fruit stays on the maze, in seconds.
+pub(crate) const FRUIT_TIME: f64 = 9.5;
+/// Bonus fruit shows up after this many dots and power ups are eaten in a level.
+pub(crate) const FRUIT_PELLETS: [u32; 2] = [70, 170];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fruit {
+    Cherry,
+    Strawberry,
+    Orange,
+    Apple,
+    Melon,
+    Galaxian,
+    Bell,
+    Key,
+}
+
+impl Fruit {
+    pub fn points(self) -> u32 {
+        match self {
+            Fruit::Cherry => 100,
+            Fruit::Strawberry => 300,
+            Fruit::Orange => 500,
+            Fruit::Apple => 700,
+            Fruit::Melon => 1000,
+            Fruit::Galaxian => 2000,
+            Fruit::Bell => 3000,
+            Fruit::Key => 5000,
+        }
+    }
+}
+
+impl fmt::Display for Fruit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Fruit::Cherry => "cherry",
+            Fruit::Strawberry => "strawberry",
+            Fruit::Orange => "orange",
+            Fruit::Apple => "apple",
+            Fruit::Melon => "melon",
+            Fruit::Galaxian => "galaxian",
+            Fruit::Bell => "bell",
+            Fruit::Key => "key",
+        })
+    }
+}
+
+impl FromStr for Fruit {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "cherry" => Ok(Fruit::Cherry),
+            "strawberry" => Ok(Fruit::Strawberry),
+            "orange" => Ok(Fruit::Orange),
+            "apple" => Ok(Fruit::Apple),
+            "melon" => Ok(Fruit::Melon),
+            "galaxian" => Ok(Fruit::Galaxian),
+            "bell" => Ok(Fruit::Bell),
+            "key" => Ok(Fruit::Key),
+            _ => Err(()),
+        }
+    }
+}
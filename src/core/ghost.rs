@@ -0,0 +1,713 @@
+pub mod brain;
+mod house;
+
+pub use self::house::{DotCounter, NextOut};
+
+use self::brain::{Decision, GhostBrain, Sight};
+use self::house::House;
+use super::level::Level;
+use super::map::{GhostSpec, Map};
+use super::motion::Motion;
+use super::save::{Reader, SaveError, Writer};
+use super::speed::units;
+use super::{ticks, Direction, Rules};
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+use std::fmt;
+use std::mem;
+use std::str::FromStr;
+
+/// Seconds frightened ghosts spend white, then blue, for each flash, 14 ticks
+/// in the arcade.
+const FLASH_TIME: f64 = 14.0 / 60.0;
+/// Speed of an eaten ghost's eyes going back to the house, in percent.
+const EYES_SPEED: u32 = 200;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum GhostMode {
+    Chase,
+    Scatter,
+    Frightened,
+}
+
+impl fmt::Display for GhostMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            GhostMode::Chase => "chase",
+            GhostMode::Scatter => "scatter",
+            GhostMode::Frightened => "frightened",
+        })
+    }
+}
+
+impl FromStr for GhostMode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "chase" => Ok(GhostMode::Chase),
+            "scatter" => Ok(GhostMode::Scatter),
+            "frightened" => Ok(GhostMode::Frightened),
+            _ => Err(()),
+        }
+    }
+}
+
+/// What a single ghost is up to, the scatter and chase phases only apply to
+/// `Normal` ghosts.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum GhostState {
+    Normal,
+    Frightened,
+    /// Only the eyes are left, going back to the house to revive.
+    Eaten,
+    /// Waiting to be let out.
+    InHouse,
+    /// On the way from the house to the tile above its door.
+    LeavingHouse,
+}
+
+impl fmt::Display for GhostState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            GhostState::Normal => "normal",
+            GhostState::Frightened => "frightened",
+            GhostState::Eaten => "eaten",
+            GhostState::InHouse => "in-house",
+            GhostState::LeavingHouse => "leaving-house",
+        })
+    }
+}
+
+impl FromStr for GhostState {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "normal" => Ok(GhostState::Normal),
+            "frightened" => Ok(GhostState::Frightened),
+            "eaten" => Ok(GhostState::Eaten),
+            "in-house" => Ok(GhostState::InHouse),
+            "leaving-house" => Ok(GhostState::LeavingHouse),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The arcade ghosts' ways of chasing Pac-Man, see `brain`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Personality {
+    Blinky,
+    Pinky,
+    Inky,
+    Clyde,
+}
+
+impl fmt::Display for Personality {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Personality::Blinky => "blinky",
+            Personality::Pinky => "pinky",
+            Personality::Inky => "inky",
+            Personality::Clyde => "clyde",
+        })
+    }
+}
+
+impl FromStr for Personality {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "blinky" => Ok(Personality::Blinky),
+            "pinky" => Ok(Personality::Pinky),
+            "inky" => Ok(Personality::Inky),
+            "clyde" => Ok(Personality::Clyde),
+            _ => Err(()),
+        }
+    }
+}
+
+pub(crate) enum Interaction {
+    KillPlayer,
+    /// Where each ghost was eaten, and how many ghosts have been eaten on the
+    /// current power up including it.
+    KillGhosts(Vec<((i32, i32), u8)>),
+}
+
+pub(crate) struct Ghosts {
+    ghosts: Vec<Ghost>,
+    /// Scatter or chase, from the level's phases.
+    mode: GhostMode,
+    /// The current phase and the ticks left in it, on hold while frightened.
+    phase: usize,
+    mode_timer: u32,
+    frightened_timer: u32,
+    /// Ghosts eaten since the last power up.
+    chain: u8,
+    house: House,
+    /// Cruise Elroy is off after a life is lost, until the last ghost is out
+    /// of the house.
+    elroy_paused: bool,
+    rules: Rules,
+}
+
+impl Ghosts {
+    pub fn new(map: &Map, rules: Rules, level: &Level) -> Self {
+        let first = level.phases[0];
+        Ghosts {
+            ghosts: map
+                .roster()
+                .iter()
+                .enumerate()
+                .map(|(i, spec)| Ghost::new(spec, map.ghost_start(i)))
+                .collect(),
+            mode: first.mode,
+            phase: 0,
+            mode_timer: first.seconds.map_or(0, |s| ticks(s, rules.tick_rate)),
+            frightened_timer: 0,
+            chain: 0,
+            house: House::new(map.roster().len()),
+            elroy_paused: false,
+            rules,
+        }
+    }
+
+    pub fn get(&self) -> &[Ghost] {
+        &self.ghosts
+    }
+
+    pub fn ghost_mode(&self) -> GhostMode {
+        if self.frightened_timer > 0 {
+            GhostMode::Frightened
+        } else {
+            self.mode
+        }
+    }
+
+    pub fn fright_remaining(&self) -> u32 {
+        self.frightened_timer
+    }
+
+    /// Whether frightened ghosts show white, they flash `flashes` times just
+    /// before recovering.
+    pub fn flashing(&self, flashes: u32) -> bool {
+        let flash = ticks(FLASH_TIME, self.rules.tick_rate);
+        let window = flashes * flash * 2;
+        let left = self.frightened_timer;
+        left > 0 && left <= window && ((window - left) / flash).is_multiple_of(2)
+    }
+
+    /// Ghosts out in the maze turn around, and are frightened for as long as
+    /// `level` says. On later levels they don't get frightened at all.
+    pub fn frighten(&mut self, level: &Level) {
+        self.turn_around();
+        let fright = ticks(level.fright, self.rules.tick_rate);
+        if fright == 0 {
+            return;
+        }
+        self.frightened_timer = fright;
+        self.chain = 0;
+        for g in self.ghosts.iter_mut() {
+            if let GhostState::Normal | GhostState::Frightened = g.state {
+                g.state = GhostState::Frightened;
+            }
+        }
+    }
+
+    /// In the arcade every ghost out in the maze turns around when their mode
+    /// changes.
+    fn turn_around(&mut self) {
+        if self.rules.arcade_ghosts {
+            for g in self.ghosts.iter_mut() {
+                g.reverse = matches!(g.state, GhostState::Normal | GhostState::Frightened);
+            }
+        }
+    }
+
+    pub fn move_ghosts(
+        &mut self,
+        map: &Map,
+        player: (i32, i32, Direction),
+        level: &Level,
+        rng: &mut ChaCha8Rng,
+    ) {
+        self.house.tick(&mut self.ghosts, level, self.rules.tick_rate);
+        if self.ghosts.iter().all(|g| g.state != GhostState::InHouse) {
+            self.elroy_paused = false;
+        }
+        let elroy = self.elroy(map, level);
+        let speeds = &level.speeds;
+        let decisions = (0..self.ghosts.len())
+            .map(|i| self.decide(i, map, player, elroy))
+            .collect::<Vec<_>>();
+        for (ghst, decision) in self.ghosts.iter_mut().zip(decisions) {
+            ghst.prev_tile = ghst.tile();
+            let (x, y) = ghst.tile();
+            let elroy = if ghst.personality == Personality::Blinky {
+                elroy
+            } else {
+                0
+            };
+            let speed = match ghst.state {
+                GhostState::Eaten => EYES_SPEED,
+                _ if map.is_tunnel(x, y) => speeds.ghost_tunnel,
+                GhostState::Frightened => speeds.ghost_fright,
+                _ if elroy == 2 => speeds.ghost_elroy2,
+                _ if elroy == 1 => speeds.ghost_elroy1,
+                _ => speeds.ghost,
+            };
+            let speed = units(speed, self.rules.tick_rate);
+            let plan = match ghst.state {
+                GhostState::Eaten => Plan::Home,
+                GhostState::InHouse => Plan::Bounce,
+                GhostState::LeavingHouse => Plan::Leave,
+                GhostState::Frightened => Plan::Flee,
+                GhostState::Normal => match decision {
+                    Decision::Target(target) => Plan::Target(target),
+                    Decision::Go(direction) => Plan::Go(direction),
+                },
+            };
+            ghst.advance(map, speed, plan, rng, self.rules);
+        }
+        if self.frightened_timer > 0 {
+            self.frightened_timer -= 1;
+            if self.frightened_timer == 0 {
+                self.chain = 0;
+                for g in self.ghosts.iter_mut() {
+                    if g.state == GhostState::Frightened {
+                        g.state = GhostState::Normal;
+                    }
+                }
+            }
+        } else if level.phases[self.phase].seconds.is_some() {
+            self.mode_timer = self.mode_timer.saturating_sub(1);
+            if self.mode_timer == 0 && self.phase + 1 < level.phases.len() {
+                self.phase += 1;
+                let next = level.phases[self.phase];
+                self.mode_timer = next.seconds.map_or(0, |s| ticks(s, self.rules.tick_rate));
+                if next.mode != self.mode {
+                    self.mode = next.mode;
+                    self.turn_around();
+                }
+            }
+        }
+    }
+
+    /// Checks for collisions with the player, who just moved from `plr_prev` to `plr`.
+    pub fn interact_with_player(
+        &mut self,
+        plr: (i32, i32),
+        plr_prev: (i32, i32),
+    ) -> Option<Interaction> {
+        let hit = |g: &Ghost| g.tile() == plr || (g.tile() == plr_prev && g.prev_tile == plr);
+        // checked first, so no ghost is eaten on the tick the player dies
+        if self
+            .ghosts
+            .iter()
+            .any(|g| hit(g) && matches!(g.state, GhostState::Normal | GhostState::LeavingHouse))
+        {
+            return Some(Interaction::KillPlayer);
+        }
+        let mut killed = vec![];
+        for g in self.ghosts.iter_mut() {
+            if g.state != GhostState::Frightened || !hit(g) {
+                continue;
+            }
+            self.chain += 1;
+            killed.push((g.tile(), self.chain));
+            g.state = GhostState::Eaten;
+        }
+        if killed.is_empty() {
+            None
+        } else {
+            Some(Interaction::KillGhosts(killed))
+        }
+    }
+
+    /// Everything back to the start of `level`, but the ghosts' brains.
+    pub fn reset(&mut self, map: &Map, level: &Level) {
+        let mut ghosts = Ghosts::new(map, self.rules, level);
+        for (new, old) in ghosts.ghosts.iter_mut().zip(self.ghosts.iter_mut()) {
+            mem::swap(&mut new.brain, &mut old.brain);
+        }
+        *self = ghosts;
+    }
+
+    /// Sends every ghost back to the house, they come out on the global dot
+    /// counter.
+    pub fn lose_life(&mut self, map: &Map, level: &Level) {
+        let mut house = self.house.clone();
+        house.lost_life();
+        self.reset(map, level);
+        self.house = house;
+        self.elroy_paused = true;
+    }
+
+    /// Asks ghost `i`'s brain where to go, in the current scatter or chase
+    /// mode.
+    fn decide(&self, i: usize, map: &Map, player: (i32, i32, Direction), elroy: u8) -> Decision {
+        let ghost = &self.ghosts[i];
+        let ghosts = self
+            .ghosts
+            .iter()
+            .map(|g| (g.personality, g.tile()))
+            .collect::<Vec<_>>();
+        let sight = Sight {
+            map,
+            tile: ghost.tile(),
+            direction: ghost.direction(),
+            corner: ghost.corner,
+            elroy: if ghost.personality == Personality::Blinky {
+                elroy
+            } else {
+                0
+            },
+            player: (player.0, player.1),
+            player_direction: player.2,
+            ghosts: &ghosts,
+        };
+        match self.mode {
+            GhostMode::Scatter => ghost.brain.scatter(&sight),
+            GhostMode::Chase | GhostMode::Frightened => ghost.brain.chase(&sight),
+        }
+    }
+
+    pub fn set_brain(&mut self, i: usize, brain: Box<dyn GhostBrain>) {
+        self.ghosts[i].brain = brain;
+    }
+
+    /// Blinky's Cruise Elroy stage, 0 when he's just Blinky. Power ups count
+    /// as pellets left, as in the arcade.
+    pub fn elroy(&self, map: &Map, level: &Level) -> u8 {
+        if self.elroy_paused {
+            0
+        } else if map.pellets() <= level.elroy2_dots {
+            2
+        } else if map.pellets() <= level.elroy1_dots {
+            1
+        } else {
+            0
+        }
+    }
+
+    pub fn dot_eaten(&mut self) {
+        self.house.dot_eaten(&self.ghosts);
+    }
+
+    pub fn next_out(&self, level: &Level) -> Option<NextOut> {
+        self.house.next_out(&self.ghosts, level, self.rules.tick_rate)
+    }
+}
+
+#[derive(Debug)]
+pub struct Ghost {
+    name: String,
+    personality: Personality,
+    corner: (i32, i32),
+    color: [u8; 3],
+    start: (i32, i32),
+    motion: Motion,
+    last_pos: (i32, i32),
+    prev_tile: (i32, i32),
+    state: GhostState,
+    /// Turns back at the next tile center.
+    reverse: bool,
+    brain: Box<dyn GhostBrain>,
+}
+
+/// How a ghost picks the next tile when it reaches a tile center.
+enum Plan {
+    Target((i32, i32)),
+    Go(Direction),
+    Flee,
+    Bounce,
+    /// Out of the house, to the tile above the door.
+    Leave,
+    /// Back into the house, through the door.
+    Home,
+}
+
+impl Ghost {
+    fn new(spec: &GhostSpec, start: (i32, i32)) -> Self {
+        Ghost {
+            name: spec.name.clone(),
+            personality: spec.personality,
+            corner: spec.corner,
+            color: spec.color,
+            start,
+            motion: Motion::at(start),
+            last_pos: (i32::MIN, i32::MIN),
+            prev_tile: start,
+            state: GhostState::InHouse,
+            reverse: false,
+            brain: brain::classic(spec.personality),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn personality(&self) -> Personality {
+        self.personality
+    }
+
+    /// RGB.
+    pub fn color(&self) -> [u8; 3] {
+        self.color
+    }
+
+    pub fn x(&self) -> i32 {
+        self.tile().0
+    }
+
+    pub fn y(&self) -> i32 {
+        self.tile().1
+    }
+
+    /// Position in tiles, in between tiles while moving.
+    pub fn position(&self) -> (f64, f64) {
+        self.motion.position()
+    }
+
+    /// The direction of travel, if moving.
+    pub fn direction(&self) -> Option<Direction> {
+        self.motion.direction()
+    }
+
+    pub fn state(&self) -> GhostState {
+        self.state
+    }
+
+    fn tile(&self) -> (i32, i32) {
+        self.motion.tile()
+    }
+
+    /// Moves `speed` sub-tile units, picking where to go next at every tile center.
+    fn advance(&mut self, map: &Map, speed: i32, plan: Plan, rng: &mut ChaCha8Rng, rules: Rules) {
+        let mut budget = speed;
+        loop {
+            if self.motion.at_center() {
+                let (x, y) = self.motion.from;
+                match plan {
+                    Plan::Home if (x, y) == self.start => {
+                        self.state = GhostState::LeavingHouse;
+                        return;
+                    }
+                    Plan::Leave if (x, y) == map.house_exit() => {
+                        self.state = GhostState::Normal;
+                        return;
+                    }
+                    _ => (),
+                }
+                let in_or_out = |x, y| map.is_house(x, y) || !map.is_wall(x, y);
+                let back = self.last_pos;
+                let next = match plan {
+                    _ if self.reverse && !map.is_wall(back.0, back.1) => {
+                        self.reverse = false;
+                        Some(back)
+                    }
+                    Plan::Target(target) => self.towards(map, target, rules),
+                    Plan::Go(direction) => {
+                        let (dx, dy) = direction.to_vector();
+                        let ahead = map.wrap(x + dx, y + dy);
+                        if ahead != back && !map.is_wall(ahead.0, ahead.1) {
+                            Some(ahead)
+                        } else {
+                            self.random_option(map, rng, rules, |x, y| !map.is_wall(x, y))
+                        }
+                    }
+                    Plan::Leave => self.closest(map, map.house_exit(), rules, in_or_out),
+                    Plan::Home => {
+                        if map.is_house(x, y) || (x, y) == map.house_exit() {
+                            self.closest(map, self.start, rules, in_or_out)
+                        } else {
+                            self.closest(map, map.house_exit(), rules, in_or_out)
+                        }
+                    }
+                    Plan::Flee => self.random_option(map, rng, rules, |x, y| !map.is_wall(x, y)),
+                    Plan::Bounce => self.random_option(map, rng, rules, |x, y| map.is_house(x, y)),
+                };
+                match next {
+                    Some(next) => self.motion.head_to(next),
+                    None => return,
+                }
+            }
+            let from = self.motion.from;
+            match self.motion.step(budget) {
+                Some(left) => {
+                    self.last_pos = from;
+                    budget = left;
+                }
+                None => return,
+            }
+        }
+    }
+
+    /// The next tile towards `target` in the maze.
+    fn towards(&self, map: &Map, target: (i32, i32), rules: Rules) -> Option<(i32, i32)> {
+        let (x, y) = self.motion.from;
+        let no_up = rules.arcade_ghosts && no_up_zone(map, (x, y));
+        let up = map.wrap(x, y - 1);
+        self.closest(map, target, rules, |ox, oy| {
+            !(map.is_wall(ox, oy) || no_up && (ox, oy) == up)
+        })
+    }
+
+    /// The `allowed` neighbouring tile closest to `target`, without turning back.
+    fn closest(
+        &self,
+        map: &Map,
+        target: (i32, i32),
+        rules: Rules,
+        allowed: impl Fn(i32, i32) -> bool,
+    ) -> Option<(i32, i32)> {
+        self.get_options(map, rules)
+            .into_iter()
+            .filter(|opt| *opt != self.last_pos)
+            .filter(|&(x, y)| allowed(x, y))
+            .min_by_key(|(x, y)| (*x - target.0).pow(2) + (*y - target.1).pow(2))
+            .or_else(|| self.dead_end(map, rules, &allowed))
+    }
+
+    fn random_option(
+        &self,
+        map: &Map,
+        rng: &mut ChaCha8Rng,
+        rules: Rules,
+        allowed: impl Fn(i32, i32) -> bool,
+    ) -> Option<(i32, i32)> {
+        let mut options = self.get_options(map, rules);
+        options.retain(|opt| *opt != self.last_pos);
+        while !options.is_empty() {
+            let i = rng.gen_range(0..options.len());
+            let opt = options.swap_remove(i);
+            if allowed(opt.0, opt.1) {
+                return Some(opt);
+            }
+        }
+        self.dead_end(map, rules, allowed)
+    }
+
+    /// The way back, when it's the only way to go.
+    fn dead_end(
+        &self,
+        map: &Map,
+        rules: Rules,
+        allowed: impl Fn(i32, i32) -> bool,
+    ) -> Option<(i32, i32)> {
+        let back = self.last_pos;
+        if self.get_options(map, rules).contains(&back) && allowed(back.0, back.1) {
+            Some(back)
+        } else {
+            None
+        }
+    }
+
+    /// The neighbouring tiles, in the order ties are broken in.
+    fn get_options(&self, map: &Map, rules: Rules) -> Vec<(i32, i32)> {
+        let (x, y) = self.motion.from;
+        let options = if rules.arcade_ghosts {
+            [(x, y - 1), (x - 1, y), (x, y + 1), (x + 1, y)]
+        } else {
+            [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)]
+        };
+        options.iter().map(|&(x, y)| map.wrap(x, y)).collect()
+    }
+}
+
+/// The tiles just above the house and just above the player's start, where
+/// chasing and scattering ghosts can't turn up in the arcade.
+fn no_up_zone(map: &Map, (x, y): (i32, i32)) -> bool {
+    let (exit_x, exit_y) = map.house_exit();
+    (exit_x - 1..=exit_x + 2).contains(&x) && (y == exit_y || y == map.player_start().1)
+}
+
+// SAVES
+impl Ghosts {
+    pub fn save(&self, w: &mut Writer) {
+        w.record(
+            "ghosts",
+            &[
+                &self.ghosts.len(),
+                &self.mode,
+                &self.phase,
+                &self.mode_timer,
+                &self.frightened_timer,
+                &self.chain,
+                &self.elroy_paused,
+            ],
+        );
+        self.house.save(w);
+        for g in self.ghosts.iter() {
+            w.record(
+                "ghost",
+                &[
+                    &g.motion.from.0,
+                    &g.motion.from.1,
+                    &g.motion.to.0,
+                    &g.motion.to.1,
+                    &g.motion.progress,
+                    &g.last_pos.0,
+                    &g.last_pos.1,
+                    &g.state,
+                    &g.reverse,
+                ],
+            );
+        }
+    }
+
+    pub fn load_state(
+        r: &mut Reader,
+        map: &Map,
+        rules: Rules,
+        level: &Level,
+    ) -> Result<Self, SaveError> {
+        let mut ghosts = Ghosts::new(map, rules, level);
+        let mut f = r.record("ghosts")?;
+        let n = f.next::<usize>()?;
+        if n != ghosts.ghosts.len() {
+            return Err(r.invalid(format!("expected {} ghosts", ghosts.ghosts.len())));
+        }
+        ghosts.mode = f.next()?;
+        ghosts.phase = f.next()?;
+        if ghosts.phase >= level.phases.len() {
+            return Err(r.invalid(format!("no phase {} in this level", ghosts.phase)));
+        }
+        ghosts.mode_timer = f.next()?;
+        ghosts.frightened_timer = f.next()?;
+        ghosts.chain = f.next()?;
+        ghosts.elroy_paused = f.next()?;
+        ghosts.house = House::load(&mut r.record("house")?, n)?;
+        for g in ghosts.ghosts.iter_mut() {
+            let mut f = r.record("ghost")?;
+            g.motion.from = f.pos()?;
+            g.motion.to = f.pos()?;
+            g.motion.progress = f.next()?;
+            g.last_pos = f.pos()?;
+            g.state = f.next()?;
+            g.reverse = f.next()?;
+        }
+        Ok(ghosts)
+    }
+}
+
+// DEBUG VIEWS
+#[allow(dead_code)]
+impl Ghosts {
+    pub fn targets(&self, map: &Map, plr: (i32, i32, Direction), level: &Level) -> Vec<(i32, i32)> {
+        let elroy = self.elroy(map, level);
+        (0..self.ghosts.len())
+            .map(|i| match self.decide(i, map, plr, elroy) {
+                _ if self.ghost_mode() == GhostMode::Frightened => (300, 300),
+                Decision::Target(t) => t,
+                Decision::Go(d) => {
+                    let (x, y) = self.ghosts[i].tile();
+                    let (dx, dy) = d.to_vector();
+                    (x + dx, y + dy)
+                }
+            })
+            .collect()
+    }
+}
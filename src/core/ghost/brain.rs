@@ -0,0 +1,115 @@
+use super::Personality;
+use crate::core::map::Map;
+use crate::core::Direction;
+use std::fmt;
+
+/// Everything a ghost's brain gets to look at when choosing where to go.
+#[derive(Clone, Copy)]
+pub struct Sight<'a> {
+    pub map: &'a Map,
+    /// The ghost's own tile and direction of travel.
+    pub tile: (i32, i32),
+    pub direction: Option<Direction>,
+    /// The ghost's scatter target, usually out of the maze near a corner.
+    pub corner: (i32, i32),
+    /// Cruise Elroy stage, 0 unless the ghost is Blinky and few pellets are left.
+    pub elroy: u8,
+    pub player: (i32, i32),
+    pub player_direction: Direction,
+    /// Every ghost's personality and tile, this one included.
+    pub ghosts: &'a [(Personality, (i32, i32))],
+}
+
+/// What a brain wants: to head for a tile, taking the turn closest to it at
+/// every tile center, or to go a given way, if there's no wall there.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Decision {
+    Target((i32, i32)),
+    Go(Direction),
+}
+
+/// A ghost's personality, consulted when it's neither frightened nor in or
+/// around the house.
+pub trait GhostBrain: fmt::Debug {
+    fn chase(&self, sight: &Sight) -> Decision;
+
+    fn scatter(&self, sight: &Sight) -> Decision {
+        Decision::Target(sight.corner)
+    }
+}
+
+/// The arcade brain for `personality`.
+pub fn classic(personality: Personality) -> Box<dyn GhostBrain> {
+    match personality {
+        Personality::Blinky => Box::new(Blinky),
+        Personality::Pinky => Box::new(Pinky),
+        Personality::Inky => Box::new(Inky),
+        Personality::Clyde => Box::new(Clyde),
+    }
+}
+
+/// Goes straight for Pac-Man, and keeps at it during scatter as Cruise Elroy.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Blinky;
+
+impl GhostBrain for Blinky {
+    fn chase(&self, sight: &Sight) -> Decision {
+        Decision::Target(sight.player)
+    }
+
+    fn scatter(&self, sight: &Sight) -> Decision {
+        if sight.elroy > 0 {
+            self.chase(sight)
+        } else {
+            Decision::Target(sight.corner)
+        }
+    }
+}
+
+/// Aims four tiles ahead of Pac-Man.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Pinky;
+
+impl GhostBrain for Pinky {
+    fn chase(&self, sight: &Sight) -> Decision {
+        Decision::Target(ahead(sight, 4))
+    }
+}
+
+/// Aims at the tile two ahead of Pac-Man, flipped around the first Blinky, or
+/// around itself when there's no Blinky.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Inky;
+
+impl GhostBrain for Inky {
+    fn chase(&self, sight: &Sight) -> Decision {
+        let blinky = sight
+            .ghosts
+            .iter()
+            .find(|(p, _)| *p == Personality::Blinky)
+            .map_or(sight.tile, |(_, tile)| *tile);
+        let mid = ahead(sight, 2);
+        Decision::Target((2 * mid.0 - blinky.0, 2 * mid.1 - blinky.1))
+    }
+}
+
+/// Goes for Pac-Man from afar, but back to its corner within 8 tiles of him.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Clyde;
+
+impl GhostBrain for Clyde {
+    fn chase(&self, sight: &Sight) -> Decision {
+        let (dx, dy) = (sight.tile.0 - sight.player.0, sight.tile.1 - sight.player.1);
+        if dx * dx + dy * dy < 64 {
+            Decision::Target(sight.corner)
+        } else {
+            Decision::Target(sight.player)
+        }
+    }
+}
+
+/// The tile `n` tiles ahead of Pac-Man.
+fn ahead(sight: &Sight, n: i32) -> (i32, i32) {
+    let (dx, dy) = sight.player_direction.to_vector();
+    (sight.player.0 + dx * n, sight.player.1 + dy * n)
+}
@@ -0,0 +1,153 @@
+use super::{Ghost, GhostState};
+use crate::core::level::Level;
+use crate::core::save::{Fields, SaveError, Writer};
+use crate::core::ticks;
+use std::fmt;
+use std::str::FromStr;
+
+/// Dots on the global counter each ghost, in order, leaves the house at. Any
+/// more ghosts leave with the last of these.
+const GLOBAL_DOTS: [u32; 4] = [0, 7, 17, 32];
+
+/// Which dot counter lets ghosts out of the house.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DotCounter {
+    /// Each ghost counts its own dots, from the level's `house_dots`.
+    Personal,
+    /// A single count shared by all ghosts, after a life is lost.
+    Global,
+}
+
+impl fmt::Display for DotCounter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            DotCounter::Personal => "personal",
+            DotCounter::Global => "global",
+        })
+    }
+}
+
+impl FromStr for DotCounter {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "personal" => Ok(DotCounter::Personal),
+            "global" => Ok(DotCounter::Global),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The next ghost to leave the house and what it's waiting for: `dots` to
+/// reach `limit` on `counter`, or `idle` ticks without a dot eaten to reach
+/// `timeout`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NextOut {
+    /// Index into the ghosts.
+    pub ghost: usize,
+    pub counter: DotCounter,
+    pub dots: u32,
+    pub limit: u32,
+    pub idle: u32,
+    pub timeout: u32,
+}
+
+/// Lets ghosts out of the house one at a time, in order, as in the arcade.
+#[derive(Clone, Debug)]
+pub(crate) struct House {
+    counter: DotCounter,
+    dots: Vec<u32>,
+    global: u32,
+    /// Ticks since the last dot was eaten.
+    idle: u32,
+}
+
+impl House {
+    pub fn new(ghosts: usize) -> Self {
+        House {
+            counter: DotCounter::Personal,
+            dots: vec![0; ghosts],
+            global: 0,
+            idle: 0,
+        }
+    }
+
+    /// Ghosts are back in the house, they now wait on the global counter.
+    pub fn lost_life(&mut self) {
+        self.counter = DotCounter::Global;
+        self.global = 0;
+        self.idle = 0;
+    }
+
+    pub fn dot_eaten(&mut self, ghosts: &[Ghost]) {
+        self.idle = 0;
+        match self.counter {
+            DotCounter::Global => self.global += 1,
+            DotCounter::Personal => {
+                if let Some(i) = preferred(ghosts) {
+                    self.dots[i] += 1;
+                }
+            }
+        }
+    }
+
+    /// Lets the next ghost out if it has waited long enough, the game running
+    /// at `rate` ticks per second.
+    pub fn tick(&mut self, ghosts: &mut [Ghost], level: &Level, rate: u32) {
+        self.idle += 1;
+        let next = match self.next_out(ghosts, level, rate) {
+            Some(next) => next,
+            None => return,
+        };
+        let i = next.ghost;
+        if next.dots < next.limit {
+            if next.idle < next.timeout {
+                return;
+            }
+            self.idle = 0;
+        }
+        ghosts[i].state = GhostState::LeavingHouse;
+        // the global counter is done with once the last ghost is out, however
+        // it got out
+        if next.counter == DotCounter::Global && i == ghosts.len() - 1 {
+            self.counter = DotCounter::Personal;
+        }
+    }
+
+    pub fn next_out(&self, ghosts: &[Ghost], level: &Level, rate: u32) -> Option<NextOut> {
+        let i = preferred(ghosts)?;
+        let (dots, limit) = match self.counter {
+            DotCounter::Personal => (self.dots[i], level.house_dots.get(i).copied().unwrap_or(0)),
+            DotCounter::Global => (self.global, GLOBAL_DOTS[i.min(GLOBAL_DOTS.len() - 1)]),
+        };
+        Some(NextOut {
+            ghost: i,
+            counter: self.counter,
+            dots,
+            limit,
+            idle: self.idle,
+            timeout: ticks(level.house_timeout, rate),
+        })
+    }
+
+    pub fn save(&self, w: &mut Writer) {
+        let mut fields: Vec<&dyn fmt::Display> = vec![&self.counter, &self.global, &self.idle];
+        fields.extend(self.dots.iter().map(|d| d as &dyn fmt::Display));
+        w.record("house", &fields);
+    }
+
+    pub fn load(f: &mut Fields, ghosts: usize) -> Result<Self, SaveError> {
+        Ok(House {
+            counter: f.next()?,
+            global: f.next()?,
+            idle: f.next()?,
+            dots: (0..ghosts).map(|_| f.next()).collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// The first ghost still waiting in the house.
+fn preferred(ghosts: &[Ghost]) -> Option<usize> {
+    ghosts.iter().position(|g| g.state == GhostState::InHouse)
+}
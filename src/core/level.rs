@@ -0,0 +1,292 @@
+use super::fruit::Fruit;
+use super::ghost::GhostMode;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const CLASSIC_LEVELS: &str = include_str!("../../levels/classic.txt");
+
+/// Everything that changes from one level to the next.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Level {
+    /// Scatter and chase phases, in order. The ghosts stay in the mode of the
+    /// last one once it's over.
+    pub phases: Vec<Phase>,
+    /// How long ghosts stay frightened after a power up, in seconds.
+    pub fright: f64,
+    /// How many times frightened ghosts flash before they recover.
+    pub flashes: u32,
+    pub fruit: Fruit,
+    pub speeds: Speeds,
+    /// Dots each ghost, in order, waits for in the house before leaving,
+    /// ghosts past the end of the list don't wait.
+    pub house_dots: Vec<u32>,
+    /// Seconds without a dot eaten before the next ghost leaves anyway.
+    pub house_timeout: f64,
+    /// Dots and power ups left when Blinky turns into Cruise Elroy, and when
+    /// he speeds up again.
+    pub elroy1_dots: u32,
+    pub elroy2_dots: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Phase {
+    pub mode: GhostMode,
+    /// `None` lasts for the rest of the level.
+    pub seconds: Option<f64>,
+}
+
+/// Percentages of full speed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Speeds {
+    pub pacman: u32,
+    pub pacman_dots: u32,
+    pub pacman_fright: u32,
+    pub pacman_fright_dots: u32,
+    pub ghost: u32,
+    pub ghost_fright: u32,
+    pub ghost_tunnel: u32,
+    pub ghost_elroy1: u32,
+    pub ghost_elroy2: u32,
+}
+
+/// The level tables, see `Levels::parse` for the file format.
+#[derive(Clone, Debug)]
+pub struct Levels {
+    source: String,
+    levels: Vec<Level>,
+}
+
+#[derive(Debug)]
+pub enum LevelsError {
+    Io(io::Error),
+    /// `line` is 1 based.
+    Invalid { line: usize, msg: String },
+}
+
+impl fmt::Display for LevelsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LevelsError::Io(e) => write!(f, "{}", e),
+            LevelsError::Invalid { line, msg } => write!(f, "line {}: {}", line, msg),
+        }
+    }
+}
+
+impl Error for LevelsError {}
+
+impl From<io::Error> for LevelsError {
+    fn from(e: io::Error) -> Self {
+        LevelsError::Io(e)
+    }
+}
+
+const KEYS: [&str; 17] = [
+    "phases",
+    "fright",
+    "flashes",
+    "fruit",
+    "pacman",
+    "pacman_dots",
+    "pacman_fright",
+    "pacman_fright_dots",
+    "ghost",
+    "ghost_fright",
+    "ghost_tunnel",
+    "ghost_elroy1",
+    "ghost_elroy2",
+    "house_dots",
+    "house_timeout",
+    "elroy1_dots",
+    "elroy2_dots",
+];
+
+impl Levels {
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, LevelsError> {
+        let src = fs::read_to_string(path)?;
+        Levels::parse(&src)
+    }
+
+    /// Parses level tables: `[level N]` sections of `key = value` lines, `#`
+    /// starts a comment. Each level starts as a copy of the one before it,
+    /// so the first has to set every key:
+    ///
+    /// `phases` (comma separated `scatter` or `chase` followed by seconds, the
+    /// last one may leave them out), `fright` (seconds), `flashes`, `fruit` and
+    /// the speeds in percent `pacman`, `pacman_dots`, `pacman_fright`,
+    /// `pacman_fright_dots`, `ghost`, `ghost_fright`, `ghost_tunnel`,
+    /// `ghost_elroy1` and `ghost_elroy2`, `house_dots` (comma separated, one
+    /// per ghost), `house_timeout` (seconds), `elroy1_dots` and `elroy2_dots`.
+    pub fn parse(src: &str) -> Result<Self, LevelsError> {
+        let mut levels: Vec<Level> = vec![];
+        let mut current: Option<(usize, Level)> = None;
+        let mut first_keys = vec![];
+        for (i, line) in src.lines().enumerate() {
+            let invalid = |msg: String| LevelsError::Invalid { line: i + 1, msg };
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let n = header
+                    .strip_prefix("level")
+                    .and_then(|n| n.trim().parse::<usize>().ok())
+                    .ok_or_else(|| invalid(format!("expected [level N], got [{}]", header)))?;
+                let next = match current.take() {
+                    Some((number, level)) => {
+                        if n <= number {
+                            return Err(invalid(format!("level {} after level {}", n, number)));
+                        }
+                        levels.resize(n - 1, level.clone());
+                        level
+                    }
+                    None if n == 1 => Level::blank(),
+                    None => return Err(invalid("the first level has to be level 1".to_string())),
+                };
+                current = Some((n, next));
+                continue;
+            }
+            let (number, level) = match &mut current {
+                Some((number, level)) => (*number, level),
+                None => return Err(invalid("expected [level 1]".to_string())),
+            };
+            let (key, value) = match line.split_once('=') {
+                Some((k, v)) => (k.trim(), v.trim()),
+                None => return Err(invalid("expected key = value".to_string())),
+            };
+            level.set(key, value).map_err(invalid)?;
+            if number == 1 {
+                first_keys.push(key);
+            }
+        }
+        let (_, last) = current.ok_or(LevelsError::Invalid {
+            line: 1,
+            msg: "no levels".to_string(),
+        })?;
+        if let Some(key) = KEYS.iter().find(|k| !first_keys.contains(k)) {
+            return Err(LevelsError::Invalid {
+                line: 1,
+                msg: format!("level 1 has no {}", key),
+            });
+        }
+        levels.push(last);
+        Ok(Levels {
+            source: src.to_string(),
+            levels,
+        })
+    }
+
+    /// Level `n`, counting from 1.
+    pub fn get(&self, n: usize) -> &Level {
+        &self.levels[n.clamp(1, self.levels.len()) - 1]
+    }
+
+    pub(crate) fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl Default for Levels {
+    fn default() -> Self {
+        Levels::parse(CLASSIC_LEVELS).expect("built-in levels are valid")
+    }
+}
+
+impl Level {
+    fn blank() -> Self {
+        Level {
+            phases: vec![],
+            fright: 0.0,
+            flashes: 0,
+            fruit: Fruit::Cherry,
+            speeds: Speeds {
+                pacman: 0,
+                pacman_dots: 0,
+                pacman_fright: 0,
+                pacman_fright_dots: 0,
+                ghost: 0,
+                ghost_fright: 0,
+                ghost_tunnel: 0,
+                ghost_elroy1: 0,
+                ghost_elroy2: 0,
+            },
+            house_dots: vec![],
+            house_timeout: 0.0,
+            elroy1_dots: 0,
+            elroy2_dots: 0,
+        }
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let number = || {
+            value
+                .parse::<u32>()
+                .map_err(|_| format!("bad {}: {}", key, value))
+        };
+        let s = &mut self.speeds;
+        match key {
+            "phases" => self.phases = parse_phases(value)?,
+            "fright" => self.fright = parse_seconds(value)?,
+            "flashes" => self.flashes = number()?,
+            "fruit" => {
+                self.fruit = value
+                    .parse()
+                    .map_err(|_| format!("unknown fruit {}", value))?
+            }
+            "pacman" => s.pacman = number()?,
+            "pacman_dots" => s.pacman_dots = number()?,
+            "pacman_fright" => s.pacman_fright = number()?,
+            "pacman_fright_dots" => s.pacman_fright_dots = number()?,
+            "ghost" => s.ghost = number()?,
+            "ghost_fright" => s.ghost_fright = number()?,
+            "ghost_tunnel" => s.ghost_tunnel = number()?,
+            "ghost_elroy1" => s.ghost_elroy1 = number()?,
+            "ghost_elroy2" => s.ghost_elroy2 = number()?,
+            "house_dots" => {
+                self.house_dots = value
+                    .split(',')
+                    .map(|n| n.trim().parse())
+                    .collect::<Result<_, _>>()
+                    .map_err(|_| format!("bad house_dots: {}", value))?
+            }
+            "house_timeout" => self.house_timeout = parse_seconds(value)?,
+            "elroy1_dots" => self.elroy1_dots = number()?,
+            "elroy2_dots" => self.elroy2_dots = number()?,
+            _ => return Err(format!("unknown key {}", key)),
+        }
+        Ok(())
+    }
+}
+
+fn parse_phases(value: &str) -> Result<Vec<Phase>, String> {
+    let parts = value.split(',').map(str::trim).collect::<Vec<_>>();
+    let mut phases = vec![];
+    for (i, part) in parts.iter().enumerate() {
+        let mut words = part.split_whitespace();
+        let mode = match words.next() {
+            Some("scatter") => GhostMode::Scatter,
+            Some("chase") => GhostMode::Chase,
+            _ => return Err(format!("expected scatter or chase, got {:?}", part)),
+        };
+        let seconds = match words.next() {
+            Some(secs) => Some(parse_seconds(secs)?),
+            None if i == parts.len() - 1 => None,
+            None => return Err(format!("only the last phase can go on forever: {}", part)),
+        };
+        if words.next().is_some() {
+            return Err(format!("expected a mode and seconds, got {:?}", part));
+        }
+        phases.push(Phase { mode, seconds });
+    }
+    Ok(phases)
+}
+
+/// Seconds, turned into ticks at whatever rate the game runs at.
+fn parse_seconds(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(s) if s >= 0.0 && s.is_finite() => Ok(s),
+        _ => Err(format!("bad time: {}", value)),
+    }
+}
@@ -0,0 +1,456 @@
+mod error;
+mod roster;
+
+pub use self::error::{LoadError, MapError};
+pub use self::roster::GhostSpec;
+
+use super::save::{Reader, SaveError, Writer};
+
+use std::fs;
+use std::path::Path;
+
+const CLASSIC_MAZE: &str = include_str!("../../mazes/classic.txt");
+
+pub struct Map {
+    source: String,
+    width: usize,
+    height: usize,
+    tiles: Vec<Tile>,
+    /// Dots and power ups left.
+    pellets: u32,
+    pellet_coords: Vec<(usize, usize)>,
+    power_up_coords: Vec<(usize, usize)>,
+    player_start: (i32, i32),
+    ghost_starts: Vec<(i32, i32)>,
+    roster: Vec<GhostSpec>,
+    door: (i32, i32),
+    tunnels: Vec<(i32, i32)>,
+    fruit: Option<(i32, i32)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tile {
+    Wall,
+    NotWall(PU),
+    House,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PU {
+    Dot,
+    PowerUp,
+    Empty,
+}
+
+impl Map {
+    pub fn new() -> Self {
+        Map::default()
+    }
+
+    /// Loads and validates a maze from a text file, see `Map::parse` for the format.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, LoadError> {
+        let src = fs::read_to_string(path)?;
+        Ok(Map::parse(&src)?)
+    }
+
+    /// Parses and validates a maze, one line per row:
+    ///
+    /// `#` wall, `.` dot, `X` power up, ` ` empty, `H` ghost house,
+    /// `-` house door, `P` player start, `0`-`9` ghost starts (inside the house)
+    /// `=` tunnel and `F` where bonus fruit shows up, if anywhere. Rows with
+    /// tunnel tiles on both edges wrap around.
+    ///
+    /// An empty line ends the maze, it can be followed by `[ghost N]`
+    /// sections to set up the ghosts (see `GhostSpec`). Without them ghosts
+    /// take turns at being Blinky, Pinky, Inky and Clyde.
+    pub fn parse(src: &str) -> Result<Self, Vec<MapError>> {
+        let lines = src.lines().collect::<Vec<_>>();
+        let grid = lines
+            .iter()
+            .position(|l| l.trim().is_empty())
+            .unwrap_or(lines.len());
+        let rows = &lines[..grid];
+        if rows.is_empty() {
+            return Err(vec![MapError::Empty]);
+        }
+        let width = rows[0].chars().count();
+        let height = rows.len();
+        let mut errors = vec![];
+        let mut tiles = Vec::with_capacity(width * height);
+        let mut pellet_coords = vec![];
+        let mut power_up_coords = vec![];
+        let mut player_start = None;
+        let mut ghost_starts = vec![];
+        let mut door = None;
+        let mut tunnels = vec![];
+        let mut fruit = None;
+        for (y, row) in rows.iter().enumerate() {
+            let len = row.chars().count();
+            if len != width {
+                errors.push(MapError::RaggedRow {
+                    row: y,
+                    len,
+                    expected: width,
+                });
+            }
+            let row_start = tiles.len();
+            for (x, c) in row.chars().enumerate() {
+                let pos = (x as i32, y as i32);
+                let tile = match c {
+                    '#' => Tile::Wall,
+                    '.' => {
+                        pellet_coords.push((x, y));
+                        Tile::NotWall(PU::Dot)
+                    }
+                    ' ' => Tile::NotWall(PU::Empty),
+                    'X' => {
+                        power_up_coords.push((x, y));
+                        Tile::NotWall(PU::PowerUp)
+                    }
+                    'H' => Tile::House,
+                    '-' => {
+                        door.get_or_insert(pos);
+                        Tile::House
+                    }
+                    'P' => {
+                        if player_start.replace(pos).is_some() {
+                            errors.push(MapError::DuplicatePlayerStart { row: y, col: x });
+                        }
+                        Tile::NotWall(PU::Empty)
+                    }
+                    '=' => {
+                        tunnels.push(pos);
+                        Tile::NotWall(PU::Empty)
+                    }
+                    'F' => {
+                        if fruit.replace(pos).is_some() {
+                            errors.push(MapError::DuplicateFruit { row: y, col: x });
+                        }
+                        Tile::NotWall(PU::Empty)
+                    }
+                    '0'..='9' => {
+                        let ghost = c as usize - '0' as usize;
+                        if ghost_starts.len() <= ghost {
+                            ghost_starts.resize(ghost + 1, None);
+                        }
+                        if ghost_starts[ghost].replace(pos).is_some() {
+                            errors.push(MapError::DuplicateGhostStart {
+                                row: y,
+                                col: x,
+                                ghost,
+                            });
+                        }
+                        Tile::House
+                    }
+                    glyph => {
+                        errors.push(MapError::UnknownGlyph {
+                            row: y,
+                            col: x,
+                            glyph,
+                        });
+                        Tile::Wall
+                    }
+                };
+                tiles.push(tile);
+            }
+            tiles.resize(row_start + width, Tile::Wall);
+        }
+        if player_start.is_none() {
+            errors.push(MapError::MissingPlayerStart);
+        }
+        ghost_starts.resize(ghost_starts.len().max(1), None);
+        for (i, g) in ghost_starts.iter().enumerate() {
+            if g.is_none() {
+                errors.push(MapError::MissingGhostStart(i));
+            }
+        }
+        if door.is_none() {
+            errors.push(MapError::MissingDoor);
+        }
+        let roster = roster::parse(
+            &lines[grid..],
+            grid,
+            ghost_starts.len(),
+            (width, height),
+            &mut errors,
+        );
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        let map = Map {
+            source: src.to_string(),
+            width,
+            height,
+            tiles,
+            pellets: (pellet_coords.len() + power_up_coords.len()) as u32,
+            pellet_coords,
+            power_up_coords,
+            player_start: player_start.unwrap(),
+            ghost_starts: ghost_starts.into_iter().flatten().collect(),
+            roster,
+            door: door.unwrap(),
+            tunnels,
+            fruit,
+        };
+        map.validate()?;
+        Ok(map)
+    }
+
+    /// Checks that the maze is playable, reporting every problem found.
+    pub fn validate(&self) -> Result<(), Vec<MapError>> {
+        let mut errors = vec![];
+        let at = |(x, y): (i32, i32)| (y as usize, x as usize);
+
+        let (x, y) = self.player_start;
+        let boxed_in = [(1, 0), (-1, 0), (0, 1), (0, -1)].iter().all(|(dx, dy)| {
+            let (x, y) = self.wrap(x + dx, y + dy);
+            self.is_wall(x, y)
+        });
+        if boxed_in {
+            let (row, col) = at(self.player_start);
+            errors.push(MapError::PlayerStartBoxedIn { row, col });
+        }
+
+        let w = self.width as i32;
+        for &(x, y) in &self.tunnels {
+            let other = if x == 0 {
+                w - 1
+            } else if x == w - 1 {
+                0
+            } else {
+                continue;
+            };
+            if !self.is_tunnel(other, y) {
+                let (row, col) = at((x, y));
+                errors.push(MapError::UnmatchedTunnel { row, col });
+            }
+        }
+
+        let exit = self.house_exit();
+        for &start in &self.ghost_starts {
+            let house = self.flood_fill(start, |t| matches!(t, Tile::House));
+            if self.is_wall(exit.0, exit.1) || !house[self.index(self.door)] {
+                let (row, col) = at(start);
+                errors.push(MapError::HouseWithoutExit { row, col });
+            }
+        }
+
+        let reachable = self.flood_fill(self.player_start, |t| matches!(t, Tile::NotWall(_)));
+        for (i, tile) in self.tiles.iter().enumerate() {
+            if let Tile::NotWall(PU::Dot) | Tile::NotWall(PU::PowerUp) = tile {
+                if !reachable[i] {
+                    errors.push(MapError::UnreachablePellet {
+                        row: i / self.width,
+                        col: i % self.width,
+                    });
+                }
+            }
+        }
+        if let Some(fruit) = self.fruit {
+            if !reachable[self.index(fruit)] {
+                let (row, col) = at(fruit);
+                errors.push(MapError::UnreachableFruit { row, col });
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn index(&self, (x, y): (i32, i32)) -> usize {
+        self.width * y as usize + x as usize
+    }
+
+    fn flood_fill(&self, from: (i32, i32), walkable: impl Fn(Tile) -> bool) -> Vec<bool> {
+        let mut seen = vec![false; self.tiles.len()];
+        let mut stack = vec![from];
+        while let Some((x, y)) = stack.pop() {
+            match self.get(x, y) {
+                Some(t) if walkable(t) && !seen[self.index((x, y))] => {
+                    seen[self.index((x, y))] = true;
+                    for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)].iter() {
+                        stack.push(self.wrap(x + dx, y + dy));
+                    }
+                }
+                _ => (),
+            }
+        }
+        seen
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The maze as it was parsed.
+    pub(crate) fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: i32, y: i32) -> Option<Tile> {
+        if x < 0 || x >= self.width as i32 || y < 0 || y >= self.height as i32 {
+            None
+        } else {
+            Some(self.tiles[self.index((x, y))])
+        }
+    }
+
+    pub fn is_wall(&self, x: i32, y: i32) -> bool {
+        !matches!(self.get(x, y), Some(Tile::NotWall(_)))
+    }
+
+    pub fn is_house(&self, x: i32, y: i32) -> bool {
+        matches!(self.get(x, y), Some(Tile::House))
+    }
+
+    pub fn is_tunnel(&self, x: i32, y: i32) -> bool {
+        self.tunnels.contains(&(x, y))
+    }
+
+    /// Wraps a position that walked off the side of a tunnel row.
+    pub fn wrap(&self, x: i32, y: i32) -> (i32, i32) {
+        let w = self.width as i32;
+        if x < 0 && self.is_tunnel(0, y) {
+            (w - 1, y)
+        } else if x >= w && self.is_tunnel(w - 1, y) {
+            (0, y)
+        } else {
+            (x, y)
+        }
+    }
+
+    pub fn player_start(&self) -> (i32, i32) {
+        self.player_start
+    }
+
+    pub fn ghost_start(&self, i: usize) -> (i32, i32) {
+        self.ghost_starts[i]
+    }
+
+    /// The ghosts, one for each start.
+    pub fn roster(&self) -> &[GhostSpec] {
+        &self.roster
+    }
+
+    /// The tile ghosts have to reach to leave the house.
+    pub fn house_exit(&self) -> (i32, i32) {
+        (self.door.0, self.door.1 - 1)
+    }
+
+    /// Where bonus fruit shows up, mazes without an `F` have none.
+    pub fn fruit_spot(&self) -> Option<(i32, i32)> {
+        self.fruit
+    }
+
+    fn set(&mut self, x: u32, y: u32, tile: Tile) {
+        let (x, y) = (x as usize, y as usize);
+        self.tiles[self.width * y + x] = tile;
+    }
+
+    pub fn consume(&mut self, x: i32, y: i32) {
+        if let Some(Tile::NotWall(PU::Dot)) | Some(Tile::NotWall(PU::PowerUp)) = self.get(x, y) {
+            self.pellets -= 1;
+        };
+        self.set(x as u32, y as u32, Tile::NotWall(PU::Empty));
+    }
+
+    pub fn scan_lines(&self) -> ScanLine<'_> {
+        ScanLine { map: self, line: 0 }
+    }
+
+    /// Dots and power ups left, the level is over when there are none.
+    pub fn pellets(&self) -> u32 {
+        self.pellets
+    }
+
+    /// Puts back every dot and power up.
+    pub fn reset(&mut self) {
+        for (x, y) in self.pellet_coords.iter().cloned() {
+            self.tiles[self.width * y + x] = Tile::NotWall(PU::Dot);
+        }
+        for (x, y) in self.power_up_coords.iter().cloned() {
+            self.tiles[self.width * y + x] = Tile::NotWall(PU::PowerUp);
+        }
+        self.pellets = (self.pellet_coords.len() + self.power_up_coords.len()) as u32;
+    }
+
+    /// Dots and power ups eaten since the maze was last reset.
+    pub fn eaten(&self) -> u32 {
+        (self.pellet_coords.len() + self.power_up_coords.len()) as u32 - self.pellets
+    }
+}
+
+// SAVES
+impl Map {
+    pub(crate) fn save(&self, w: &mut Writer) {
+        w.record("maze", &[&self.source.lines().count()]);
+        for line in self.source.lines() {
+            w.raw(line);
+        }
+        let fresh = Map::parse(&self.source).expect("a map's source is valid");
+        let eaten = (0..self.tiles.len())
+            .filter(|&i| self.tiles[i] != fresh.tiles[i])
+            .map(|i| format!("{} {}", i % self.width, i / self.width))
+            .collect::<Vec<_>>();
+        w.record("eaten", &[&eaten.join(" ")]);
+    }
+
+    pub(crate) fn load_state(r: &mut Reader) -> Result<Self, SaveError> {
+        let rows = r.record("maze")?.next::<usize>()?;
+        let mut src = String::new();
+        for _ in 0..rows {
+            src.push_str(r.raw()?);
+            src.push('\n');
+        }
+        let mut map = Map::parse(&src).map_err(|e| r.invalid(format!("bad maze: {}", e[0])))?;
+        let mut f = r.record("eaten")?;
+        while let Ok((x, y)) = f.pos() {
+            if map.get(x, y).is_none() {
+                return Err(r.invalid(format!("{} {} is outside the maze", x, y)));
+            }
+            // counts down the pellets left too
+            map.consume(x, y);
+        }
+        Ok(map)
+    }
+}
+
+impl Default for Map {
+    fn default() -> Self {
+        Map::parse(CLASSIC_MAZE).expect("built-in maze is valid")
+    }
+}
+
+pub struct ScanLine<'a> {
+    map: &'a Map,
+    line: usize,
+}
+
+impl<'a> Iterator for ScanLine<'a> {
+    type Item = &'a [Tile];
+
+    fn next(&mut self) -> Option<&'a [Tile]> {
+        let line_start = self.line * self.map.width;
+        self.line += 1;
+        if line_start >= self.map.tiles.len() {
+            None
+        } else {
+            Some(&self.map.tiles[line_start..(line_start + self.map.width)])
+        }
+    }
+}
+
+// DEBUG
+#[allow(dead_code)]
+impl Map {
+    pub fn remove_all_pellets(&mut self) {
+        self.pellets = 0;
+    }
+}
@@ -0,0 +1,112 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// Something wrong with a maze. Rows and columns are 0 based, they are shown
+/// 1 based (like line numbers) when displayed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapError {
+    Empty,
+    UnknownGlyph { row: usize, col: usize, glyph: char },
+    RaggedRow { row: usize, len: usize, expected: usize },
+    MissingPlayerStart,
+    DuplicatePlayerStart { row: usize, col: usize },
+    PlayerStartBoxedIn { row: usize, col: usize },
+    MissingGhostStart(usize),
+    DuplicateGhostStart { row: usize, col: usize, ghost: usize },
+    MissingDoor,
+    HouseWithoutExit { row: usize, col: usize },
+    UnreachablePellet { row: usize, col: usize },
+    UnmatchedTunnel { row: usize, col: usize },
+    DuplicateFruit { row: usize, col: usize },
+    UnreachableFruit { row: usize, col: usize },
+    BadRoster { row: usize, msg: String },
+}
+
+impl MapError {
+    pub fn position(&self) -> Option<(usize, usize)> {
+        use MapError::*;
+        match *self {
+            UnknownGlyph { row, col, .. }
+            | DuplicatePlayerStart { row, col }
+            | PlayerStartBoxedIn { row, col }
+            | DuplicateGhostStart { row, col, .. }
+            | HouseWithoutExit { row, col }
+            | UnreachablePellet { row, col }
+            | UnmatchedTunnel { row, col }
+            | DuplicateFruit { row, col }
+            | UnreachableFruit { row, col } => Some((row, col)),
+            RaggedRow { row, .. } | BadRoster { row, .. } => Some((row, 0)),
+            Empty | MissingPlayerStart | MissingGhostStart(_) | MissingDoor => None,
+        }
+    }
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use MapError::*;
+        if let Some((row, col)) = self.position() {
+            write!(f, "{}:{}: ", row + 1, col + 1)?;
+        }
+        match self {
+            Empty => write!(f, "maze is empty"),
+            UnknownGlyph { glyph, .. } => write!(f, "unknown glyph {:?}", glyph),
+            RaggedRow { len, expected, .. } => {
+                write!(f, "row is {} tiles wide, expected {}", len, expected)
+            }
+            MissingPlayerStart => write!(f, "no player start"),
+            DuplicatePlayerStart { .. } => write!(f, "more than one player start"),
+            PlayerStartBoxedIn { .. } => write!(f, "player can't move from the start"),
+            MissingGhostStart(g) => write!(f, "no start for ghost {}", g),
+            DuplicateGhostStart { ghost, .. } => {
+                write!(f, "more than one start for ghost {}", ghost)
+            }
+            MissingDoor => write!(f, "ghost house has no door"),
+            HouseWithoutExit { .. } => write!(f, "ghost can't leave the house"),
+            UnreachablePellet { .. } => write!(f, "pellet can't be reached"),
+            UnmatchedTunnel { .. } => write!(f, "tunnel has no exit on the other side"),
+            DuplicateFruit { .. } => write!(f, "more than one fruit spot"),
+            UnreachableFruit { .. } => write!(f, "fruit can't be reached"),
+            BadRoster { msg, .. } => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl Error for MapError {}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    Invalid(Vec<MapError>),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Io(e) => write!(f, "{}", e),
+            LoadError::Invalid(errors) => {
+                for (i, e) in errors.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{}", e)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for LoadError {}
+
+impl From<io::Error> for LoadError {
+    fn from(e: io::Error) -> Self {
+        LoadError::Io(e)
+    }
+}
+
+impl From<Vec<MapError>> for LoadError {
+    fn from(e: Vec<MapError>) -> Self {
+        LoadError::Invalid(e)
+    }
+}
@@ -0,0 +1,124 @@
+use super::MapError;
+use crate::core::ghost::Personality;
+
+/// One of a maze's ghosts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GhostSpec {
+    /// Picks the ghost's picture, `images/<name>.png`, if there's one.
+    pub name: String,
+    pub personality: Personality,
+    /// Where it heads when scattering, usually just out of the maze.
+    pub corner: (i32, i32),
+    /// RGB, for pictures and terminals without one of their own.
+    pub color: [u8; 3],
+}
+
+impl GhostSpec {
+    /// The arcade ghost with `personality`, in a `width` by `height` maze.
+    pub fn classic(personality: Personality, width: usize, height: usize) -> Self {
+        let (w, h) = (width as i32, height as i32);
+        let (corner, color) = match personality {
+            Personality::Blinky => ((w - 3, -2), [255, 0, 0]),
+            Personality::Pinky => ((2, -2), [255, 184, 255]),
+            Personality::Inky => ((w - 1, h), [0, 255, 255]),
+            Personality::Clyde => ((0, h), [255, 184, 82]),
+        };
+        GhostSpec {
+            name: personality.to_string(),
+            personality,
+            corner,
+            color,
+        }
+    }
+}
+
+/// The arcade's ghosts, taken in turn for mazes without a roster.
+const CLASSIC: [Personality; 4] = [
+    Personality::Blinky,
+    Personality::Pinky,
+    Personality::Inky,
+    Personality::Clyde,
+];
+
+/// The roster for `ghosts` ghosts, from the `[ghost N]` sections of `lines`
+/// (starting at row `first_row` of the maze file). Each ghost starts as a
+/// classic one, the sections change it with `key = value` lines: `name`,
+/// `personality` (`blinky`, `pinky`, `inky` or `clyde`), `corner` (column and
+/// row) and `color` (hex `rrggbb`, without a `#`).
+pub(super) fn parse(
+    lines: &[&str],
+    first_row: usize,
+    ghosts: usize,
+    (width, height): (usize, usize),
+    errors: &mut Vec<MapError>,
+) -> Vec<GhostSpec> {
+    let mut roster = (0..ghosts)
+        .map(|i| GhostSpec::classic(CLASSIC[i % CLASSIC.len()], width, height))
+        .collect::<Vec<_>>();
+    let mut current = None;
+    for (i, line) in lines.iter().enumerate() {
+        let row = first_row + i;
+        let mut fail = |msg: String| errors.push(MapError::BadRoster { row, msg });
+        let line = line.split('#').next().unwrap().trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            current = header
+                .strip_prefix("ghost")
+                .and_then(|n| n.trim().parse::<usize>().ok());
+            match current {
+                Some(n) if n >= ghosts => fail(format!("no start for ghost {}", n)),
+                Some(_) => (),
+                None => fail(format!("expected [ghost N], got [{}]", header)),
+            }
+            continue;
+        }
+        let spec = match current.and_then(|n| roster.get_mut(n)) {
+            Some(spec) => spec,
+            None if current.is_some() => continue,
+            None => {
+                fail("expected [ghost N]".to_string());
+                continue;
+            }
+        };
+        let (key, value) = match line.split_once('=') {
+            Some((k, v)) => (k.trim(), v.trim()),
+            None => {
+                fail("expected key = value".to_string());
+                continue;
+            }
+        };
+        if let Err(msg) = set(spec, key, value) {
+            fail(msg);
+        }
+    }
+    roster
+}
+
+fn set(spec: &mut GhostSpec, key: &str, value: &str) -> Result<(), String> {
+    let bad = || format!("bad {}: {}", key, value);
+    match key {
+        "name" if !value.is_empty() => spec.name = value.to_string(),
+        "personality" => spec.personality = value.parse().map_err(|_| bad())?,
+        "corner" => {
+            let mut xy = value.split(',').map(|n| n.trim().parse::<i32>());
+            spec.corner = match (xy.next(), xy.next(), xy.next()) {
+                (Some(Ok(x)), Some(Ok(y)), None) => (x, y),
+                _ => return Err(bad()),
+            }
+        }
+        "color" => {
+            // `#` starts a comment, so there's never one in front
+            spec.color = match u32::from_str_radix(value, 16) {
+                Ok(rgb) if value.len() == 6 && !value.starts_with('+') => {
+                    [(rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8]
+                }
+                _ => return Err(bad()),
+            }
+        }
+        "name" => return Err(bad()),
+        _ => return Err(format!("unknown key {}", key)),
+    }
+    Ok(())
+}
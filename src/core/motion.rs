@@ -0,0 +1,106 @@
+use super::Direction;
+
+/// Sub-tile units in a tile.
+pub const SUBTILE: i32 = 256;
+
+/// Movement between tile centers, in sub-tile units.
+///
+/// An entity leaves the center of `from` towards the center of `to`, `progress`
+/// units along the way. It is stopped when both are the same tile. Turns can
+/// only be taken at a tile center.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Motion {
+    pub from: (i32, i32),
+    pub to: (i32, i32),
+    pub progress: i32,
+}
+
+impl Motion {
+    pub fn at(pos: (i32, i32)) -> Self {
+        Motion {
+            from: pos,
+            to: pos,
+            progress: 0,
+        }
+    }
+
+    pub fn at_center(&self) -> bool {
+        self.progress == 0
+    }
+
+    /// The tile the entity is mostly in.
+    pub fn tile(&self) -> (i32, i32) {
+        if self.progress * 2 >= SUBTILE {
+            self.to
+        } else {
+            self.from
+        }
+    }
+
+    /// The direction of travel, if moving.
+    pub fn direction(&self) -> Option<Direction> {
+        match self.step_vector() {
+            (0, -1) => Some(Direction::Up),
+            (0, 1) => Some(Direction::Down),
+            (-1, 0) => Some(Direction::Left),
+            (1, 0) => Some(Direction::Right),
+            _ => None,
+        }
+    }
+
+    /// The position in tiles, between `from` and `to`. When going through a
+    /// tunnel this is off the side of the maze until `to` is reached.
+    pub fn position(&self) -> (f64, f64) {
+        let (dx, dy) = self.step_vector();
+        let p = self.progress as f64 / SUBTILE as f64;
+        (
+            self.from.0 as f64 + dx as f64 * p,
+            self.from.1 as f64 + dy as f64 * p,
+        )
+    }
+
+    /// Unit vector from `from` to `to`, taking tunnels into account.
+    fn step_vector(&self) -> (i32, i32) {
+        let wrapped = |d: i32| if d.abs() > 1 { -d.signum() } else { d };
+        (
+            wrapped(self.to.0 - self.from.0),
+            wrapped(self.to.1 - self.from.1),
+        )
+    }
+
+    /// Sets off towards a neighbouring tile, only valid at a tile center.
+    pub fn head_to(&mut self, to: (i32, i32)) {
+        debug_assert!(self.at_center());
+        self.to = to;
+    }
+
+    pub fn stop(&mut self) {
+        self.to = self.from;
+        self.progress = 0;
+    }
+
+    /// Turns back around, halfway through a tile.
+    pub fn reverse(&mut self) {
+        if self.progress > 0 {
+            std::mem::swap(&mut self.from, &mut self.to);
+            self.progress = SUBTILE - self.progress;
+        }
+    }
+
+    /// Moves up to `budget` units towards `to`. Returns the units left over if the
+    /// center of `to` was reached on the way.
+    pub fn step(&mut self, budget: i32) -> Option<i32> {
+        if self.from == self.to {
+            return None;
+        }
+        let left = SUBTILE - self.progress;
+        if budget < left {
+            self.progress += budget;
+            None
+        } else {
+            self.from = self.to;
+            self.progress = 0;
+            Some(budget - left)
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use super::level::Levels;
+use super::map::Map;
+use super::{Direction, Pacman, Rules};
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+const HEADER: &str = "pac-replay 1";
+
+/// Everything needed to play a game again: the seed, the rules, the maze, the
+/// level tables and every input along with the tick it was given on. The maze
+/// and levels are kept whole, so a replay plays the same anywhere.
+#[derive(Debug, Clone, Default)]
+pub struct Replay {
+    pub seed: u64,
+    pub rules: Rules,
+    maze: String,
+    levels: String,
+    inputs: Vec<(u32, Direction)>,
+}
+
+impl Replay {
+    /// Starts recording `game`, which hasn't been played yet.
+    pub fn new(game: &Pacman) -> Self {
+        Replay {
+            seed: game.seed(),
+            rules: game.rules(),
+            maze: game.map().source().to_string(),
+            levels: game.levels().source().to_string(),
+            inputs: vec![],
+        }
+    }
+
+    /// The game as it was when recording started.
+    pub fn game(&self) -> Pacman {
+        let map = Map::parse(&self.maze).expect("a replay's maze is valid");
+        let levels = Levels::parse(&self.levels).expect("a replay's levels are valid");
+        Pacman::with_levels(map, self.seed, self.rules, levels)
+    }
+
+    /// Records an input given after `tick` ticks.
+    pub fn record(&mut self, tick: u32, direction: Direction) {
+        self.inputs.push((tick, direction));
+    }
+
+    pub fn inputs(&self) -> &[(u32, Direction)] {
+        &self.inputs
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut f = io::BufWriter::new(fs::File::create(path)?);
+        writeln!(f, "{}", HEADER)?;
+        writeln!(f, "seed {}", self.seed)?;
+        writeln!(f, "tick-rate {}", self.rules.tick_rate)?;
+        for (key, src) in [("maze", &self.maze), ("levels", &self.levels)].iter() {
+            writeln!(f, "{} {}", key, src.lines().count())?;
+            for line in src.lines() {
+                writeln!(f, "{}", line)?;
+            }
+        }
+        writeln!(f, "arcade-ghosts {}", self.rules.arcade_ghosts)?;
+        writeln!(f, "extra-life {}", self.rules.extra_life)?;
+        writeln!(f, "extra-life-every {}", self.rules.extra_life_every)?;
+        writeln!(f, "max-lives {}", self.rules.max_lives)?;
+        for (tick, direction) in &self.inputs {
+            writeln!(f, "{} {}", tick, direction)?;
+        }
+        f.flush()
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let src = fs::read_to_string(path)?;
+        let invalid = |line: usize, msg: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("line {}: {}", line + 1, msg),
+            )
+        };
+        let mut lines = src.lines().enumerate();
+        match lines.next() {
+            Some((_, HEADER)) => (),
+            _ => return Err(invalid(0, "not a replay file")),
+        }
+        let mut replay = Replay::default();
+        // where the maze and levels were, to point at when they don't parse
+        let (mut maze, mut levels) = (None, None);
+        while let Some((i, line)) = lines.next() {
+            let (key, value) = match line.split_once(' ') {
+                Some(kv) => kv,
+                None if line.trim().is_empty() => continue,
+                None => return Err(invalid(i, "expected '<key> <value>'")),
+            };
+            match key {
+                "seed" => replay.seed = value.parse().map_err(|_| invalid(i, "bad seed"))?,
+                "tick-rate" => {
+                    replay.rules.tick_rate = value
+                        .parse()
+                        .ok()
+                        .filter(|rate| *rate > 0)
+                        .ok_or_else(|| invalid(i, "bad tick rate"))?
+                }
+                "maze" | "levels" => {
+                    let rows = value.parse().map_err(|_| invalid(i, "bad line count"))?;
+                    let mut src = String::new();
+                    for _ in 0..rows {
+                        let (_, line) = lines
+                            .next()
+                            .ok_or_else(|| invalid(i, "unexpected end of file"))?;
+                        src.push_str(line);
+                        src.push('\n');
+                    }
+                    if key == "maze" {
+                        replay.maze = src;
+                        maze = Some(i);
+                    } else {
+                        replay.levels = src;
+                        levels = Some(i);
+                    }
+                }
+                "arcade-ghosts" => {
+                    replay.rules.arcade_ghosts =
+                        value.parse().map_err(|_| invalid(i, "bad rule"))?
+                }
+                "extra-life" => {
+                    replay.rules.extra_life = value.parse().map_err(|_| invalid(i, "bad rule"))?
+                }
+                "extra-life-every" => {
+                    replay.rules.extra_life_every =
+                        value.parse().map_err(|_| invalid(i, "bad rule"))?
+                }
+                "max-lives" => {
+                    replay.rules.max_lives = value.parse().map_err(|_| invalid(i, "bad rule"))?
+                }
+                tick => {
+                    let tick = tick.parse().map_err(|_| invalid(i, "bad tick"))?;
+                    let direction = value.parse().map_err(|_| invalid(i, "bad direction"))?;
+                    replay.record(tick, direction);
+                }
+            }
+        }
+        let maze = maze.ok_or_else(|| invalid(0, "no maze"))?;
+        if let Err(e) = Map::parse(&replay.maze) {
+            return Err(invalid(maze, &format!("bad maze: {}", e[0])));
+        }
+        let levels = levels.ok_or_else(|| invalid(0, "no levels"))?;
+        if let Err(e) = Levels::parse(&replay.levels) {
+            return Err(invalid(levels, &format!("bad levels: {}", e)));
+        }
+        Ok(replay)
+    }
+}
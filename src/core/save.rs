@@ -0,0 +1,129 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::io;
+use std::str::{FromStr, Lines, SplitWhitespace};
+
+pub(crate) const HEADER: &str = "pac-save";
+pub(crate) const VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(io::Error),
+    NotASave,
+    Version(u32),
+    Invalid { line: usize, msg: String },
+}
+
+impl Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SaveError::Io(e) => write!(f, "{}", e),
+            SaveError::NotASave => write!(f, "not a save file"),
+            SaveError::Version(v) => write!(
+                f,
+                "save is from version {}, only version {} is supported",
+                v, VERSION
+            ),
+            SaveError::Invalid { line, msg } => write!(f, "line {}: {}", line, msg),
+        }
+    }
+}
+
+impl Error for SaveError {}
+
+impl From<io::Error> for SaveError {
+    fn from(e: io::Error) -> Self {
+        SaveError::Io(e)
+    }
+}
+
+/// Line based writer, each record is a key followed by space separated fields.
+#[derive(Default)]
+pub(crate) struct Writer {
+    out: String,
+}
+
+impl Writer {
+    pub fn record(&mut self, key: &str, fields: &[&dyn Display]) {
+        self.out.push_str(key);
+        for f in fields {
+            self.out.push(' ');
+            self.out.push_str(&f.to_string());
+        }
+        self.out.push('\n');
+    }
+
+    pub fn raw(&mut self, line: &str) {
+        self.out.push_str(line);
+        self.out.push('\n');
+    }
+
+    pub fn finish(self) -> String {
+        self.out
+    }
+}
+
+pub(crate) struct Reader<'a> {
+    lines: Lines<'a>,
+    line: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(src: &'a str) -> Self {
+        Reader {
+            lines: src.lines(),
+            line: 0,
+        }
+    }
+
+    /// Reads the next line, which has to be a `key` record.
+    pub fn record(&mut self, key: &str) -> Result<Fields<'a>, SaveError> {
+        let line = self.raw()?;
+        let mut tokens = line.split_whitespace();
+        if tokens.next() != Some(key) {
+            return Err(self.invalid(format!("expected {}", key)));
+        }
+        Ok(Fields {
+            tokens,
+            line: self.line,
+        })
+    }
+
+    pub fn raw(&mut self) -> Result<&'a str, SaveError> {
+        self.line += 1;
+        let line = self.line;
+        self.lines.next().ok_or_else(|| SaveError::Invalid {
+            line,
+            msg: "unexpected end of file".to_string(),
+        })
+    }
+
+    pub fn invalid(&self, msg: String) -> SaveError {
+        SaveError::Invalid {
+            line: self.line,
+            msg,
+        }
+    }
+}
+
+pub(crate) struct Fields<'a> {
+    tokens: SplitWhitespace<'a>,
+    line: usize,
+}
+
+impl<'a> Fields<'a> {
+    pub fn next<T: FromStr>(&mut self) -> Result<T, SaveError> {
+        let line = self.line;
+        self.tokens
+            .next()
+            .and_then(|t| t.parse().ok())
+            .ok_or_else(|| SaveError::Invalid {
+                line,
+                msg: "missing or malformed field".to_string(),
+            })
+    }
+
+    pub fn pos(&mut self) -> Result<(i32, i32), SaveError> {
+        Ok((self.next()?, self.next()?))
+    }
+}
@@ -0,0 +1,235 @@
+use super::Stats;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const HEADER: &str = "pac-scores 1";
+/// Scores kept in the table.
+pub const MAX_SCORES: usize = 10;
+/// Letters in a player's initials.
+pub const INITIALS: usize = 3;
+
+/// A game worth remembering.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HighScore {
+    /// Up to `INITIALS` letters from `A` to `Z`.
+    pub initials: String,
+    pub score: u32,
+    /// The level the game ended on.
+    pub level: usize,
+    /// `YYYY-MM-DD`, in UTC.
+    pub date: String,
+    pub seed: u64,
+    /// The maze's file name without its extension, `classic` for the built-in
+    /// one.
+    pub maze: String,
+}
+
+impl HighScore {
+    /// A game that ended today with `stats`.
+    pub fn new(initials: &str, stats: Stats, seed: u64, maze: &str) -> Self {
+        HighScore {
+            initials: initials.to_string(),
+            score: stats.score,
+            level: stats.level,
+            date: today(),
+            seed,
+            maze: maze.to_string(),
+        }
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.splitn(6, ' ');
+        let score = fields.next()?.parse().ok()?;
+        let level = fields.next()?.parse().ok()?;
+        let date = fields.next()?.to_string();
+        let seed = fields.next()?.parse().ok()?;
+        let initials = fields.next()?.to_string();
+        let maze = fields.next()?.to_string();
+        if !valid_initials(&initials) || maze.is_empty() {
+            return None;
+        }
+        Some(HighScore {
+            initials,
+            score,
+            level,
+            date,
+            seed,
+            maze,
+        })
+    }
+}
+
+/// What the maze loaded from `path` goes by in the table: its file name
+/// without the extension, `classic` for the built-in one.
+pub fn maze_name(path: Option<&str>) -> String {
+    path.and_then(|p| Path::new(p).file_stem())
+        .map_or("classic".to_string(), |m| m.to_string_lossy().into_owned())
+}
+
+/// Whether `initials` are 1 to `INITIALS` letters from `A` to `Z`.
+pub fn valid_initials(initials: &str) -> bool {
+    (1..=INITIALS).contains(&initials.len()) && initials.bytes().all(|b| b.is_ascii_uppercase())
+}
+
+/// The best `MAX_SCORES` games, best first.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HighScores {
+    scores: Vec<HighScore>,
+}
+
+impl HighScores {
+    /// Reads the table saved at `path`, an empty one if there's none yet. Lines
+    /// that don't make sense are left out rather than losing the whole table
+    /// to a bad write or a hand edit.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let src = match fs::read(path) {
+            Ok(src) => src,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(HighScores::default()),
+            Err(e) => return Err(e),
+        };
+        Ok(HighScores::parse(&String::from_utf8_lossy(&src)))
+    }
+
+    /// Reads a table, see `HighScores::load`.
+    pub fn parse(src: &str) -> Self {
+        let mut table = HighScores::default();
+        for score in src
+            .lines()
+            .filter(|l| *l != HEADER)
+            .filter_map(HighScore::parse)
+        {
+            table.insert(score);
+        }
+        table
+    }
+
+    /// Writes the table to `path`, through a temporary file so a failed write
+    /// doesn't lose the old one.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let tmp = path.with_extension("tmp");
+        let mut f = io::BufWriter::new(fs::File::create(&tmp)?);
+        writeln!(f, "{}", HEADER)?;
+        for s in &self.scores {
+            writeln!(
+                f,
+                "{} {} {} {} {} {}",
+                s.score, s.level, s.date, s.seed, s.initials, s.maze
+            )?;
+        }
+        f.into_inner()?.sync_all()?;
+        fs::rename(tmp, path)
+    }
+
+    pub fn scores(&self) -> &[HighScore] {
+        &self.scores
+    }
+
+    /// The best score, 0 for an empty table.
+    pub fn best(&self) -> u32 {
+        self.scores.first().map_or(0, |s| s.score)
+    }
+
+    /// Whether `score` would make it into the table.
+    pub fn qualifies(&self, score: u32) -> bool {
+        score > 0 && (self.scores.len() < MAX_SCORES || score > self.scores[MAX_SCORES - 1].score)
+    }
+
+    /// Adds `score` below any equal ones and returns its place, counting from
+    /// 0, or `None` if it didn't make it.
+    pub fn insert(&mut self, score: HighScore) -> Option<usize> {
+        let at = self.scores.partition_point(|s| s.score >= score.score);
+        if at >= MAX_SCORES {
+            return None;
+        }
+        self.scores.insert(at, score);
+        self.scores.truncate(MAX_SCORES);
+        Some(at)
+    }
+}
+
+/// Initials entered arcade style, a letter at a time: the current letter is
+/// picked by cycling through the alphabet, or typed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Initials {
+    taken: String,
+    current: u8,
+}
+
+impl Default for Initials {
+    fn default() -> Self {
+        Initials {
+            taken: String::new(),
+            current: b'A',
+        }
+    }
+}
+
+impl Initials {
+    /// The letters taken so far.
+    pub fn taken(&self) -> &str {
+        &self.taken
+    }
+
+    pub fn current(&self) -> char {
+        self.current as char
+    }
+
+    /// Moves the current letter `by` letters along the alphabet, wrapping
+    /// around.
+    pub fn cycle(&mut self, by: i32) {
+        self.current = b'A' + (self.current as i32 - b'A' as i32 + by).rem_euclid(26) as u8;
+    }
+
+    /// Takes the current letter.
+    pub fn take(&mut self) {
+        if !self.is_done() {
+            self.taken.push(self.current as char);
+        }
+    }
+
+    /// Takes `letter`, ignoring anything but letters.
+    pub fn type_letter(&mut self, letter: char) {
+        if letter.is_ascii_alphabetic() {
+            self.current = letter.to_ascii_uppercase() as u8;
+            self.take();
+        }
+    }
+
+    /// Goes back to the last letter taken.
+    pub fn back(&mut self) {
+        if let Some(c) = self.taken.pop() {
+            self.current = c as u8;
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.taken.len() == INITIALS
+    }
+}
+
+/// Today's date, `YYYY-MM-DD` in UTC.
+fn today() -> String {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let (y, m, d) = civil_date((secs / 86_400) as i64);
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+/// The year, month and day `days` after 1970-01-01, from Howard Hinnant's
+/// `civil_from_days`.
+fn civil_date(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    (y, m, d)
+}
@@ -0,0 +1,15 @@
+use super::motion::SUBTILE;
+
+/// Tiles per second at 100% speed, the arcade's 75.75 pixels per second.
+const FULL_SPEED: f64 = 75.757_576 / 8.0;
+
+/// Sub-tile units moved per tick at `percent` of full speed and `rate` ticks
+/// per second. Anything faster than standing still moves at least one unit.
+pub(crate) fn units(percent: u32, rate: u32) -> i32 {
+    let units = (FULL_SPEED * SUBTILE as f64 * percent as f64 / 100.0 / rate as f64).round() as i32;
+    if percent > 0 {
+        units.max(1)
+    } else {
+        0
+    }
+}
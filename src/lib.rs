@@ -0,0 +1,10 @@
+//! The pacman game rules, with no windowing or rendering dependencies.
+//!
+//! [`core::Pacman`] is the whole game: feed it input with
+//! [`core::Pacman::set_direction_intent`], advance it with [`core::Pacman::tick`]
+//! and query its state to draw it however you like. [`clock::FixedStep`] helps
+//! calling `tick` at a steady rate.
+
+pub mod cli;
+pub mod clock;
+pub mod core;
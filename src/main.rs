@@ -1,5 +1,4 @@
 mod controler;
-mod pacman;
 mod view;
 
 use glutin_window::GlutinWindow as Window;
@@ -8,11 +7,57 @@ use piston::event_loop::{EventSettings, Events};
 use piston::input::{RenderEvent, ResizeEvent};
 use piston::window::WindowSettings;
 
+use rust_gaem::cli::{self, Args};
+use rust_gaem::core::replay::Replay;
+use rust_gaem::core::Rules;
+
 use crate::controler::Controler;
-use crate::pacman::Pacman;
 use crate::view::View;
 
+const USAGE: &str =
+    "usage: pac [--tick-rate <hz>] [--seed <seed>] [--simple-ghosts] [--levels <file>]
+           [--extra-life <points>] [--extra-life-every <points>] [--record <file>] [maze]
+       pac --replay <file>";
+
 fn main() {
+    let mut record = None;
+    let mut replay = None;
+    let args = Args::parse(USAGE, |flag, argv| {
+        match flag {
+            "--record" => record = Some(argv.value()),
+            "--replay" => replay = Some(argv.value()),
+            _ => return false,
+        }
+        true
+    });
+    if replay.is_some()
+        && (args.maze.is_some()
+            || args.levels.is_some()
+            || args.seed.is_some()
+            || args.rules != Rules::default()
+            || record.is_some())
+    {
+        cli::usage(USAGE);
+    }
+    let mut controler = match &replay {
+        Some(path) => {
+            let replay = Replay::load(path).unwrap_or_else(|e| {
+                eprintln!("{}: {}", path, e);
+                std::process::exit(1)
+            });
+            Controler::replaying(replay)
+        }
+        None => {
+            let game = args.game();
+            eprintln!("seed: {}", game.seed());
+            if record.is_some() {
+                Controler::recording(game, args.maze.as_deref())
+            } else {
+                Controler::new(game, args.maze.as_deref())
+            }
+        }
+    };
+
     let opengl = OpenGL::V3_2;
 
     let mut window: Window = WindowSettings::new("pacman-game", [750, 750])
@@ -22,8 +67,7 @@ fn main() {
         .unwrap();
 
     let mut gl = GlGraphics::new(opengl);
-    let mut controler = Controler::new(Pacman::new());
-    let mut view = View::new();
+    let mut view = View::new(controler.get_ghosts());
 
     let mut events = Events::new(EventSettings::new());
     while let Some(e) = events.next(&mut window) {
@@ -33,11 +77,15 @@ fn main() {
                 view.draw(&controler, &c, g);
             })
         } else if let Some(r) = e.resize_args() {
-            view.resize(r.window_size[0], r.window_size[1]);
-        } else {
-            if controler.event(&e) {
-                break;
-            }
+            view.resize(r.window_size[0], r.window_size[1], controler.get_map());
+        } else if controler.event(&e) {
+            break;
+        }
+    }
+
+    if let Some(path) = &record {
+        if let Err(e) = controler.save_recording(path) {
+            eprintln!("Failed to save replay to {}: {}", path, e);
         }
     }
 }
@@ -1,51 +1,63 @@
+mod text;
+
+use self::text::{Align, Label, Text};
 use crate::controler::Controler;
-use crate::pacman::map::{Tile, MAP_HEIGHT, MAP_WIDTH, PU};
-use crate::pacman::Direction;
-use graphics::{circle_arc::CircleArc, image::Image, rectangle::Rectangle, types::Color, Context};
+use graphics::{
+    circle_arc::CircleArc, ellipse::Ellipse, image::Image, rectangle::Rectangle, types::Color,
+    Context,
+};
 use opengl_graphics::GlGraphics;
 use opengl_graphics::Texture;
+use rust_gaem::core::fruit::Fruit;
+use rust_gaem::core::ghost::{Ghost, GhostState};
+use rust_gaem::core::map::{Map, Tile, PU};
+use rust_gaem::core::{Direction, GamePhase};
+use std::collections::HashMap;
+use std::f64::consts::PI;
 use std::path::Path;
 use texture::TextureSettings;
 
 pub struct View {
     wall_color: Color,
-    ghost_textures: [Texture; 4],
+    /// By ghost name, the others are `ghost` tinted with their color.
+    ghost_textures: HashMap<String, Texture>,
+    ghost: Texture,
     frightened: Texture,
-    numbers: Vec<Texture>,
+    frightened_flash: Texture,
     pacmans: [Texture; 4],
-    fruits: [Texture; 20],
+    fruits: [Texture; 8],
+    text: Text,
     dot_color: Color,
     tile_size: f64,
     x_offset: f64,
     y_offset: f64,
 }
 
+const WHITE: Color = [1.0; 4];
+const YELLOW: Color = [1.0, 1.0, 0.0, 1.0];
+const RED: Color = [1.0, 0.0, 0.0, 1.0];
+const CYAN: Color = [0.0, 1.0, 1.0, 1.0];
+
 fn load_image(name: &str) -> Texture {
-    Texture::from_path(
-        Path::new(&format!("images/{}.png", name)),
-        &TextureSettings::new(),
-    )
-    .expect(&format!("Failed to load: {}", name))
+    load_texture(name, &TextureSettings::new())
+}
+
+fn load_texture(name: &str, settings: &TextureSettings) -> Texture {
+    Texture::from_path(Path::new(&format!("images/{}.png", name)), settings)
+        .unwrap_or_else(|e| panic!("Failed to load {}: {}", name, e))
 }
 
 impl View {
-    pub fn new() -> Self {
-        let ghost_textures = {
-            let mut textures = Vec::with_capacity(4);
-            for name in ["blinky", "pinky", "inky", "clyde"].iter() {
-                textures.push(load_image(name));
-            }
-            [
-                textures.remove(0),
-                textures.remove(0),
-                textures.remove(0),
-                textures.remove(0),
-            ]
-        };
+    pub fn new(ghosts: &[Ghost]) -> Self {
+        let ghost_textures = ghosts
+            .iter()
+            .map(Ghost::name)
+            .filter(|name| Path::new(&format!("images/{}.png", name)).exists())
+            .map(|name| (name.to_string(), load_image(name)))
+            .collect();
+        let ghost = load_image("ghost");
         let frightened = load_image("frightened");
-        let numbers = (0..10)
-            .map(|i| load_image(&i.to_string()))
-            .collect::<Vec<_>>();
+        let frightened_flash = load_image("frightened_flash");
         let pacmans = [
             load_image("pacman_up"),
             load_image("pacman_right"),
@@ -56,31 +68,21 @@ impl View {
             load_image("cherry"),
             load_image("strawberry"),
             load_image("orange"),
-            load_image("orange"),
             load_image("apple"),
-            load_image("apple"),
-            load_image("mellon"),
             load_image("mellon"),
             load_image("flower"),
-            load_image("flower"),
             load_image("bell"),
-            load_image("bell"),
-            load_image("key"),
-            load_image("key"),
-            load_image("key"),
-            load_image("key"),
-            load_image("key"),
-            load_image("key"),
-            load_image("key"),
             load_image("key"),
         ];
         View {
             wall_color: [0.1294, 0.1294, 0.8706, 1.0],
             ghost_textures,
+            ghost,
             frightened,
-            numbers,
+            frightened_flash,
             pacmans,
             fruits,
+            text: Text::new(),
             dot_color: [1.0, 1.0, 1.0, 1.0],
             tile_size: 20.0,
             x_offset: 0.0,
@@ -88,11 +90,13 @@ impl View {
         }
     }
 
-    pub fn resize(&mut self, x: f64, y: f64) {
-        self.tile_size = y / (MAP_HEIGHT + 6) as f64;
-        let blankspace = x - (MAP_WIDTH as f64 * self.tile_size);
+    pub fn resize(&mut self, x: f64, y: f64, map: &Map) {
+        self.tile_size = y / (map.height() + 6) as f64;
+        let blankspace = x - (map.width() as f64 * self.tile_size);
         self.x_offset = blankspace / 2.0;
         self.y_offset = self.tile_size * 2.0;
+        self.text
+            .resize(self.tile_size, [self.x_offset, self.y_offset]);
     }
 
     pub fn draw(&self, controler: &Controler, c: &Context, g: &mut GlGraphics) {
@@ -101,9 +105,19 @@ impl View {
             a[1] += self.y_offset;
             a
         };
+        let map = controler.get_map();
+        let (map_width, map_height) = (map.width(), map.height());
+        let alpha = controler.tick_progress();
+        let phase = controler.get_phase();
+        let progress = controler.phase_progress();
+        // the cleared maze flashes four times before the next level
+        let wall_color = match phase {
+            GamePhase::LevelComplete if (progress * 8.0) as u32 % 2 == 1 => [1.0; 4],
+            _ => self.wall_color,
+        };
         let mut x = 0.0;
         let mut y = 0.0;
-        for line in controler.get_map().scan_lines() {
+        for line in map.scan_lines() {
             for tile in line.iter() {
                 match tile {
                     Tile::Wall => {
@@ -113,7 +127,7 @@ impl View {
                             self.tile_size / 2.0,
                             self.tile_size / 2.0,
                         ]);
-                        Rectangle::new(self.wall_color).draw(sqr, &c.draw_state, c.transform, g);
+                        Rectangle::new(wall_color).draw(sqr, &c.draw_state, c.transform, g);
                     }
                     Tile::NotWall(PU::Dot) => {
                         let sqr = offset([
@@ -131,7 +145,7 @@ impl View {
                             self.tile_size / 4.0,
                             self.tile_size / 4.0,
                         ]);
-                        CircleArc::new(self.dot_color, self.tile_size / 4.0, 0.0, 2.0 * 3.14).draw(
+                        CircleArc::new(self.dot_color, self.tile_size / 4.0, 0.0, 2.0 * PI).draw(
                             sqr,
                             &c.draw_state,
                             c.transform,
@@ -149,57 +163,75 @@ impl View {
         let stats = controler.get_stats();
         {
             // Stats
-            let mut sc = stats.score;
-            let mut i = -1;
-            while sc > 0 {
-                sc /= 10;
-                i += 1;
-            }
-            sc = stats.score;
-            while sc > 0 {
-                let d = sc % 10;
-                let sq = offset([
-                    (MAP_WIDTH / 2) as f64 * self.tile_size + i as f64 * self.tile_size * 1.702,
-                    self.tile_size * -2.0,
-                    self.tile_size,
-                    self.tile_size * 1.702,
-                ]);
-                Image::new().rect(sq).draw(
-                    &self.numbers[d as usize],
-                    &c.draw_state,
-                    c.transform,
-                    g,
-                );
-                sc = sc / 10;
-                i -= 1;
+            let (mid, right) = (map_width as f64 / 2.0, map_width as f64 - 1.0);
+            // the best score yet, including this game's
+            let best = controler.get_high_scores().best().max(stats.score);
+            let (score, best, level) = (
+                stats.score.to_string(),
+                best.to_string(),
+                stats.level.to_string(),
+            );
+            for label in [
+                Label::new("SCORE", 1.0, -2.0),
+                Label::new(&score, 1.0, -1.0),
+                Label::new("HIGH SCORE", mid, -2.0).align(Align::Center),
+                Label::new(&best, mid, -1.0).align(Align::Center),
+                Label::new("LEVEL", right, -2.0).align(Align::Right),
+                Label::new(&level, right, -1.0).align(Align::Right),
+            ]
+            .iter()
+            {
+                self.text.draw(&label.size(0.9), c, g);
             }
 
-            for i in 0..stats.lives {
+            let lives = stats.lives.saturating_sub(controler.new_life_blink() as u8);
+
+            for i in 0..lives {
                 Image::new()
                     .rect(offset(
-                        self.entity_sq(i as i32 * 2, (MAP_HEIGHT + 1) as i32),
+                        self.entity_sq(i as f64 * 2.0, (map_height + 1) as f64),
                     ))
                     .draw(&self.pacmans[3], &c.draw_state, c.transform, g);
             }
 
-            let cap_at_20 = |i| if i > 20 { 20 } else { i };
-            let cap_at_13 = |i| if i > 13 { 13 } else { i };
-            self.fruits[cap_at_13(stats.level.saturating_sub(7))..cap_at_20(stats.level)]
-                .iter()
-                .enumerate()
-                .for_each(|(i, t)| {
-                    Image::new()
-                        .rect(offset(self.entity_sq(
-                            (MAP_WIDTH - i - 1) as i32,
-                            (MAP_HEIGHT + 1) as i32,
-                        )))
-                        .draw(t, &c.draw_state, c.transform, g)
-                });
+            // the last seven levels' fruit, up to level 20
+            let last = stats.level.min(20);
+            let first = stats.level.saturating_sub(7).min(13) + 1;
+            for (i, level) in (first..=last).enumerate() {
+                Image::new()
+                    .rect(offset(self.entity_sq(
+                        (map_width - i - 1) as f64,
+                        (map_height + 1) as f64,
+                    )))
+                    .draw(
+                        self.fruit_texture(controler.get_level_fruit(level)),
+                        &c.draw_state,
+                        c.transform,
+                        g,
+                    );
+            }
         }
 
-        if stats.lives > 0 {
+        if let Some((fruit, (x, y))) = controler.get_fruit() {
+            Image::new()
+                .rect(offset(self.entity_sq(x as f64, y as f64)))
+                .draw(self.fruit_texture(fruit), &c.draw_state, c.transform, g);
+        }
+
+        // everything freezes for the first half of a death, then the ghosts go
+        // away and Pac-Man shrivels up
+        let dying = phase == GamePhase::Dying && progress >= 0.5;
+        if dying {
+            let (x, y) = controler.get_player_position();
+            self.draw_death((progress - 0.5) * 2.0, x, y, c, g);
+        } else if phase != GamePhase::GameOver {
             // pacman
-            let (x, y, d) = controler.get_player();
+            let (_, _, d) = controler.get_player();
+            let (x, y) = interpolate(
+                controler.get_previous_player_position(),
+                controler.get_player_position(),
+                alpha,
+            );
             let pac_texture = match d {
                 Direction::Up => &self.pacmans[0],
                 Direction::Right => &self.pacmans[1],
@@ -214,23 +246,58 @@ impl View {
             );
         }
 
-        let pick_color = |c| {
-            if controler.frightened() {
-                &self.frightened
-            } else {
-                c
-            }
-        };
+        let previous = controler.get_previous_ghost_positions();
+        let hidden = dying || matches!(phase, GamePhase::LevelComplete | GamePhase::GameOver);
+        let ghosts = if hidden { &[] } else { controler.get_ghosts() };
+        for (i, ghost) in ghosts.iter().enumerate() {
+            let (x, y) = interpolate(previous[i], ghost.position(), alpha);
+            let texture = match ghost.state() {
+                GhostState::Eaten => {
+                    self.draw_eyes(x, y, ghost.direction(), c, g);
+                    continue;
+                }
+                GhostState::Frightened if controler.fright_flash() => &self.frightened_flash,
+                GhostState::Frightened => &self.frightened,
+                _ => match self.ghost_textures.get(ghost.name()) {
+                    Some(texture) => texture,
+                    None => {
+                        let [r, gr, b] = ghost.color();
+                        let tint = [r as f32 / 255.0, gr as f32 / 255.0, b as f32 / 255.0, 1.0];
+                        Image::new_color(tint)
+                            .rect(offset(self.entity_sq(x, y)))
+                            .draw(&self.ghost, &c.draw_state, c.transform, g);
+                        self.draw_eyes(x, y, ghost.direction(), c, g);
+                        continue;
+                    }
+                },
+            };
+            Image::new().rect(offset(self.entity_sq(x, y))).draw(
+                texture,
+                &c.draw_state,
+                c.transform,
+                g,
+            );
+        }
 
-        for (i, ghost) in controler.get_ghosts().iter().enumerate() {
-            Image::new()
-                .rect(offset(self.entity_sq(ghost.x(), ghost.y())))
-                .draw(
-                    pick_color(&self.ghost_textures[i]),
-                    &c.draw_state,
-                    c.transform,
-                    g,
-                );
+        for popup in controler.get_popups() {
+            let (x, y) = popup.pos;
+            let points = popup.points.to_string();
+            let label = Label::new(&points, x as f64 + 0.5, y as f64)
+                .size(0.85)
+                .align(Align::Center)
+                .color(CYAN);
+            self.text.draw(&label, c, g);
+        }
+
+        // between the house and the player's start, as in the arcade
+        let row = (map.house_exit().1 + map.player_start().1) / 2;
+        let message =
+            |text| Label::new(text, map_width as f64 / 2.0, row as f64).align(Align::Center);
+        match phase {
+            GamePhase::GameOver => self.draw_game_over(controler, c, g),
+            _ if controler.is_paused() => self.draw_message(&message("PAUSED"), c, g),
+            GamePhase::Ready => self.draw_message(&message("READY!").color(YELLOW), c, g),
+            _ => (),
         }
 
         // DEBUG
@@ -242,16 +309,173 @@ impl View {
         //         [1.0, 0.7216, 0.3176, 1.0],
         //     ];
         //         Rectangle::new_border(ghost_colors[i], 1.0)
-        //             .draw(offset(self.entity_sq(sqr.0, sqr.1)), &c.draw_state, c.transform, g);
+        //             .draw(offset(self.entity_sq(sqr.0 as f64, sqr.1 as f64)), &c.draw_state, c.transform, g);
         // }
     }
 
-    fn entity_sq(&self, x: i32, y: i32) -> [f64; 4] {
+    /// Draws a ghost's eyes at tile position `x`, `y`, looking the way they're
+    /// going.
+    fn draw_eyes(
+        &self,
+        x: f64,
+        y: f64,
+        direction: Option<Direction>,
+        c: &Context,
+        g: &mut GlGraphics,
+    ) {
+        let (dx, dy) = direction.map_or((0, 0), Direction::to_vector);
+        let ts = self.tile_size;
+        for &eye in [0.18, 0.52].iter() {
+            let left = self.x_offset + (x + eye) * ts;
+            let top = self.y_offset + (y + 0.2) * ts;
+            Ellipse::new([1.0, 1.0, 1.0, 1.0]).draw(
+                [left, top, 0.3 * ts, 0.4 * ts],
+                &c.draw_state,
+                c.transform,
+                g,
+            );
+            Ellipse::new([0.1294, 0.1294, 0.8706, 1.0]).draw(
+                [
+                    left + (0.08 + dx as f64 * 0.07) * ts,
+                    top + (0.12 + dy as f64 * 0.1) * ts,
+                    0.15 * ts,
+                    0.15 * ts,
+                ],
+                &c.draw_state,
+                c.transform,
+                g,
+            );
+        }
+    }
+
+    /// Draws Pac-Man at tile position `x`, `y` dying, `t` of the way through:
+    /// his mouth opens up until there's nothing left of him.
+    fn draw_death(&self, t: f64, x: f64, y: f64, c: &Context, g: &mut GlGraphics) {
+        let open = t.min(1.0) * PI;
+        if open >= PI {
+            return;
+        }
+        let ts = self.tile_size;
+        let sq = [
+            self.x_offset + (x + 0.25) * ts,
+            self.y_offset + (y + 0.25) * ts,
+            ts / 2.0,
+            ts / 2.0,
+        ];
+        let up = -PI / 2.0;
+        CircleArc::new(
+            [1.0, 1.0, 0.0, 1.0],
+            ts / 4.0,
+            up + open,
+            up + 2.0 * PI - open,
+        )
+        .draw(sq, &c.draw_state, c.transform, g);
+    }
+
+    /// Draws `label` on a black background.
+    fn draw_message(&self, label: &Label, c: &Context, g: &mut GlGraphics) {
+        let ts = self.tile_size;
+        Rectangle::new([0.0, 0.0, 0.0, 1.0]).draw(
+            [
+                self.x_offset + (label.left() - 0.5) * ts,
+                self.y_offset + label.top() * ts,
+                (label.width() + 1.0) * ts,
+                label.height() * ts,
+            ],
+            &c.draw_state,
+            c.transform,
+            g,
+        );
+        self.text.draw(label, c, g);
+    }
+
+    /// Draws "GAME OVER" over the middle of the maze, with the high scores or,
+    /// when the game made it into them, the initials being entered.
+    fn draw_game_over(&self, controler: &Controler, c: &Context, g: &mut GlGraphics) {
+        const LINES: f64 = 16.0;
+        let map = controler.get_map();
+        let (width, height) = (map.width() as f64, map.height() as f64);
+        // the panel's line height, shrunk to fit small mazes
+        let line = (height / LINES).min(1.0);
+        let top = (height - LINES * line) / 2.0;
+        let ts = self.tile_size;
+        Rectangle::new([0.0, 0.0, 0.0, 1.0]).draw(
+            [
+                self.x_offset,
+                self.y_offset + top * ts,
+                width * ts,
+                LINES * line * ts,
+            ],
+            &c.draw_state,
+            c.transform,
+            g,
+        );
+        let line_at = |text: &str, row: f64, color: Color, g: &mut GlGraphics| {
+            let label = Label::new(text, width / 2.0, top + row * line)
+                .size(line)
+                .align(Align::Center)
+                .color(color);
+            self.text.draw(&label, c, g);
+        };
+        line_at("GAME OVER", 0.0, RED, g);
+        if let Some(initials) = controler.get_initials() {
+            line_at("ENTER YOUR INITIALS", 2.0, WHITE, g);
+            // the letters taken, then the current one in yellow
+            let shown = format!("{}{}", initials.taken(), initials.current());
+            let left = Label::new(&shown, width / 2.0, 0.0)
+                .size(line * 2.0)
+                .align(Align::Center)
+                .left();
+            let taken = Label::new(initials.taken(), left, top + 5.0 * line).size(line * 2.0);
+            self.text.draw(&taken, c, g);
+            let current = initials.current().to_string();
+            let current = Label::new(&current, left + taken.width(), taken.top())
+                .size(line * 2.0)
+                .color(YELLOW);
+            self.text.draw(&current, c, g);
+            return;
+        }
+        line_at("HIGH SCORES", 2.0, WHITE, g);
+        for (i, s) in controler.get_high_scores().scores().iter().enumerate() {
+            let text = format!(
+                "{:>2} {:<3} {:>7} L{:<2}",
+                i + 1,
+                s.initials,
+                s.score,
+                s.level
+            );
+            let color = match controler.get_new_high_score() {
+                Some(new) if new == i => YELLOW,
+                _ => WHITE,
+            };
+            line_at(&text, 4.0 + i as f64, color, g);
+        }
+        line_at("PRESS ENTER", 15.0, WHITE, g);
+    }
+
+    fn fruit_texture(&self, fruit: Fruit) -> &Texture {
+        &self.fruits[fruit as usize]
+    }
+
+    fn entity_sq(&self, x: f64, y: f64) -> [f64; 4] {
         [
-            x as f64 * self.tile_size,
-            y as f64 * self.tile_size,
+            x * self.tile_size,
+            y * self.tile_size,
             self.tile_size,
             self.tile_size,
         ]
     }
 }
+
+/// Where an entity is `alpha` of the way from its position at the last tick to
+/// the current one. A jump of more than a tile is a tunnel wrap or a respawn,
+/// which snaps rather than sliding across the maze.
+fn interpolate(from: (f64, f64), to: (f64, f64), alpha: f64) -> (f64, f64) {
+    if (to.0 - from.0).abs() > 1.0 || (to.1 - from.1).abs() > 1.0 {
+        return to;
+    }
+    (
+        from.0 + (to.0 - from.0) * alpha,
+        from.1 + (to.1 - from.1) * alpha,
+    )
+}
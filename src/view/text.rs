@@ -0,0 +1,135 @@
+use graphics::{image::Image, types::Color, Context};
+use opengl_graphics::{GlGraphics, Texture};
+use texture::{Filter, TextureSettings};
+
+/// `images/font.png` has a glyph for every character from `FIRST` to `LAST`,
+/// in rows of `COLUMNS`.
+const FIRST: char = ' ';
+const LAST: char = '~';
+const COLUMNS: u32 = 16;
+/// Pixels from one glyph to the next in the font, each glyph is a pixel
+/// narrower and shorter.
+const CELL: (f64, f64) = (4.0, 6.0);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+/// A line of text on the maze's tile grid.
+#[derive(Clone, Copy, Debug)]
+pub struct Label<'a> {
+    text: &'a str,
+    x: f64,
+    y: f64,
+    size: f64,
+    align: Align,
+    color: Color,
+}
+
+impl<'a> Label<'a> {
+    /// `text`, white and a tile tall, starting at tile `x` with its top on
+    /// row `y`.
+    pub fn new(text: &'a str, x: f64, y: f64) -> Self {
+        Label {
+            text,
+            x,
+            y,
+            size: 1.0,
+            align: Align::Left,
+            color: [1.0; 4],
+        }
+    }
+
+    /// Makes it `size` tiles tall.
+    pub fn size(self, size: f64) -> Self {
+        Label { size, ..self }
+    }
+
+    /// Where `x` is along the text, its start by default.
+    pub fn align(self, align: Align) -> Self {
+        Label { align, ..self }
+    }
+
+    pub fn color(self, color: Color) -> Self {
+        Label { color, ..self }
+    }
+
+    /// How much of a row the text takes up, in tiles.
+    pub fn width(&self) -> f64 {
+        self.text.chars().count() as f64 * self.size * CELL.0 / CELL.1
+    }
+
+    pub fn top(&self) -> f64 {
+        self.y
+    }
+
+    /// In tiles.
+    pub fn height(&self) -> f64 {
+        self.size
+    }
+
+    /// The tile the text starts at.
+    pub fn left(&self) -> f64 {
+        match self.align {
+            Align::Left => self.x,
+            Align::Center => self.x - self.width() / 2.0,
+            Align::Right => self.x - self.width(),
+        }
+    }
+}
+
+/// Draws labels in a blocky bitmap font, sized and placed in tiles.
+pub struct Text {
+    font: Texture,
+    tile_size: f64,
+    /// Where the top left tile is on screen.
+    origin: [f64; 2],
+}
+
+impl Text {
+    pub fn new() -> Self {
+        // scaled up as is, not blurred
+        let settings = TextureSettings::new().filter(Filter::Nearest);
+        Text {
+            font: super::load_texture("font", &settings),
+            tile_size: 20.0,
+            origin: [0.0, 0.0],
+        }
+    }
+
+    /// Lays text out on tiles `tile_size` pixels wide, with the top left one
+    /// at `origin` on screen.
+    pub fn resize(&mut self, tile_size: f64, origin: [f64; 2]) {
+        self.tile_size = tile_size;
+        self.origin = origin;
+    }
+
+    /// Draws `label`, characters missing from the font show up as `?`.
+    pub fn draw(&self, label: &Label, c: &Context, g: &mut GlGraphics) {
+        let px = label.size * self.tile_size / CELL.1;
+        let left = self.origin[0] + label.left() * self.tile_size;
+        let top = self.origin[1] + label.y * self.tile_size;
+        let image = Image::new_color(label.color);
+        for (i, ch) in label.text.chars().enumerate() {
+            let ch = if (FIRST..=LAST).contains(&ch) {
+                ch
+            } else {
+                '?'
+            };
+            let n = ch as u32 - FIRST as u32;
+            let (column, row) = ((n % COLUMNS) as f64, (n / COLUMNS) as f64);
+            image
+                .src_rect([column * CELL.0, row * CELL.1, CELL.0 - 1.0, CELL.1 - 1.0])
+                .rect([
+                    left + i as f64 * CELL.0 * px,
+                    top,
+                    (CELL.0 - 1.0) * px,
+                    (CELL.1 - 1.0) * px,
+                ])
+                .draw(&self.font, &c.draw_state, c.transform, g);
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use rust_gaem::core::ghost::brain::{Blinky, Clyde, Decision, GhostBrain, Inky, Pinky, Sight};
+use rust_gaem::core::ghost::{GhostState, Personality};
+use rust_gaem::core::map::Map;
+use rust_gaem::core::{Direction, GamePhase, Pacman, TICKS_PER_SECOND};
+use std::cell::Cell;
+use std::rc::Rc;
+
+const CORNER: (i32, i32) = (0, -2);
+
+fn sight<'a>(map: &'a Map, tile: (i32, i32), ghosts: &'a [(Personality, (i32, i32))]) -> Sight<'a> {
+    Sight {
+        map,
+        tile,
+        direction: Some(Direction::Left),
+        corner: CORNER,
+        elroy: 0,
+        player: (14, 23),
+        player_direction: Direction::Right,
+        ghosts,
+    }
+}
+
+#[test]
+fn blinky_goes_for_pacman() {
+    let map = Map::new();
+    let s = sight(&map, (1, 1), &[]);
+    assert_eq!(Blinky.chase(&s), Decision::Target((14, 23)));
+    assert_eq!(Blinky.scatter(&s), Decision::Target(CORNER));
+}
+
+#[test]
+fn cruise_elroy_keeps_chasing_in_scatter() {
+    let map = Map::new();
+    let s = Sight {
+        elroy: 1,
+        ..sight(&map, (1, 1), &[])
+    };
+    assert_eq!(Blinky.scatter(&s), Decision::Target((14, 23)));
+}
+
+#[test]
+fn pinky_aims_ahead_of_pacman() {
+    let map = Map::new();
+    let s = sight(&map, (1, 1), &[]);
+    assert_eq!(Pinky.chase(&s), Decision::Target((18, 23)));
+    let s = Sight {
+        player_direction: Direction::Up,
+        ..s
+    };
+    assert_eq!(Pinky.chase(&s), Decision::Target((14, 19)));
+}
+
+#[test]
+fn inky_flips_around_blinky() {
+    let map = Map::new();
+    let ghosts = [(Personality::Blinky, (10, 20)), (Personality::Inky, (1, 1))];
+    let s = sight(&map, (1, 1), &ghosts);
+    // two ahead of Pac-Man is (16, 23), 6 right and 3 down of Blinky
+    assert_eq!(Inky.chase(&s), Decision::Target((22, 26)));
+}
+
+#[test]
+fn inky_without_blinky_flips_around_itself() {
+    let map = Map::new();
+    let ghosts = [(Personality::Inky, (16, 20))];
+    let s = sight(&map, (16, 20), &ghosts);
+    assert_eq!(Inky.chase(&s), Decision::Target((16, 26)));
+}
+
+#[test]
+fn clyde_backs_off_when_close() {
+    let map = Map::new();
+    assert_eq!(
+        Clyde.chase(&sight(&map, (14, 31), &[])),
+        Decision::Target((14, 23))
+    );
+    assert_eq!(
+        Clyde.chase(&sight(&map, (14, 25), &[])),
+        Decision::Target(CORNER)
+    );
+    assert_eq!(
+        Clyde.scatter(&sight(&map, (1, 1), &[])),
+        Decision::Target(CORNER)
+    );
+}
+
+#[derive(Debug)]
+struct Fixed((i32, i32));
+
+impl GhostBrain for Fixed {
+    fn chase(&self, _: &Sight) -> Decision {
+        Decision::Target(self.0)
+    }
+
+    fn scatter(&self, _: &Sight) -> Decision {
+        Decision::Target(self.0)
+    }
+}
+
+#[test]
+fn custom_brains_are_consulted() {
+    let mut game = Pacman::with_map(Map::new(), 42);
+    game.set_brain(2, Box::new(Fixed((3, 4))));
+    assert_eq!(game.ghost_targets()[2], (3, 4));
+}
+
+/// Goes for Pac-Man, counting how many times it's asked where to go.
+#[derive(Debug)]
+struct Counting(Rc<Cell<u32>>);
+
+impl GhostBrain for Counting {
+    fn chase(&self, sight: &Sight) -> Decision {
+        self.0.set(self.0.get() + 1);
+        Decision::Target(sight.player)
+    }
+
+    fn scatter(&self, sight: &Sight) -> Decision {
+        self.chase(sight)
+    }
+}
+
+/// Pac-Man next to the house, between the only two dots.
+const TWO_DOTS: &str = "\
+###########
+#.P.      #
+#####-#####
+####H0123##
+###########
+";
+
+/// Ticks `game` until `done`.
+fn until(game: &mut Pacman, done: impl Fn(&Pacman) -> bool) {
+    for _ in 0..TICKS_PER_SECOND * 10 {
+        if done(game) {
+            return;
+        }
+        game.tick();
+    }
+    panic!("took too long");
+}
+
+#[test]
+fn custom_brains_outlive_lives_and_levels() {
+    let calls = Rc::new(Cell::new(0));
+    let mut game = Pacman::with_map(Map::parse(TWO_DOTS).unwrap(), 42);
+    game.set_brain(0, Box::new(Counting(calls.clone())));
+    // Pac-Man eats the dot on his left and waits there for Blinky
+    until(&mut game, |game| game.stats().lives == 4);
+    assert!(calls.get() > 0);
+
+    let before = calls.get();
+    until(&mut game, |game| {
+        game.phase() == GamePhase::Playing && game.ghosts()[0].state() == GhostState::Normal
+    });
+    game.tick();
+    assert!(calls.get() > before, "not asked after a life was lost");
+
+    game.set_direction_intent(Direction::Right);
+    until(&mut game, |game| game.stats().level == 2);
+    let before = calls.get();
+    until(&mut game, |game| {
+        game.phase() == GamePhase::Playing && game.ghosts()[0].state() == GhostState::Normal
+    });
+    game.tick();
+    assert!(calls.get() > before, "not asked on the next level");
+}
@@ -0,0 +1,81 @@
+use rust_gaem::clock::FixedStep;
+use rust_gaem::core::ghost::GhostMode;
+use rust_gaem::core::map::Map;
+use rust_gaem::core::{GamePhase, Pacman, Rules};
+
+#[test]
+fn leftover_time_carries_to_the_next_frame() {
+    let mut clock = FixedStep::new(4.0, 8);
+    assert_eq!(clock.advance(0.625), 2);
+    assert_eq!(clock.alpha(), 0.5);
+    assert_eq!(clock.advance(0.125), 1);
+    assert_eq!(clock.alpha(), 0.0);
+}
+
+#[test]
+fn catching_up_is_capped_at_max_steps() {
+    let mut clock = FixedStep::new(4.0, 3);
+    assert_eq!(clock.advance(10.125), 3);
+    // the dropped ticks are gone, only the time towards the next one is kept
+    assert_eq!(clock.alpha(), 0.5);
+    assert_eq!(clock.advance(0.125), 1);
+}
+
+#[test]
+fn alpha_is_the_way_to_the_next_tick() {
+    let mut clock = FixedStep::new(4.0, 8);
+    assert_eq!(clock.tick(), 0.25);
+    assert_eq!(clock.alpha(), 0.0);
+    assert_eq!(clock.advance(0.0625), 0);
+    assert_eq!(clock.alpha(), 0.25);
+    assert_eq!(clock.advance(0.125), 0);
+    assert_eq!(clock.alpha(), 0.75);
+    clock.reset();
+    assert_eq!(clock.alpha(), 0.0);
+}
+
+#[test]
+#[should_panic]
+fn rejects_a_zero_rate() {
+    FixedStep::new(0.0, 4);
+}
+
+/// A long empty corridor for Pac-Man, who walks left at a constant speed,
+/// away from the ghosts.
+const CORRIDOR: &str = "\
+##############################
+#     ########################
+####-#########################
+###H0123######################
+##############################
+#                          P.#
+##############################
+";
+
+#[test]
+fn tick_rate_keeps_the_pace() {
+    let seconds = |game: &mut Pacman, s: f64| {
+        for _ in 0..(s * game.tick_rate() as f64).round() as u32 {
+            game.tick();
+        }
+    };
+    let mut positions = vec![];
+    for &rate in &[30, 60, 120] {
+        let rules = Rules {
+            tick_rate: rate,
+            ..Rules::default()
+        };
+        let mut game = Pacman::with_rules(Map::parse(CORRIDOR).unwrap(), 1, rules);
+        // "READY!" lasts as long at any rate too
+        seconds(&mut game, 2.0);
+        assert_eq!(game.phase(), GamePhase::Playing, "at {} Hz", rate);
+        seconds(&mut game, 1.0);
+        assert_eq!(game.ghost_mode(), GhostMode::Scatter, "at {} Hz", rate);
+        positions.push(game.player_position());
+        seconds(&mut game, 6.5);
+        assert_eq!(game.ghost_mode(), GhostMode::Chase, "at {} Hz", rate);
+    }
+    for p in &positions {
+        assert!((p.0 - positions[1].0).abs() < 0.25, "{:?}", positions);
+    }
+}
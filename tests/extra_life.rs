@@ -0,0 +1,72 @@
+use rust_gaem::core::map::Map;
+use rust_gaem::core::{Event, Pacman, Rules};
+
+fn extra_lives(game: &mut Pacman) -> Vec<u8> {
+    game.take_events()
+        .into_iter()
+        .filter_map(|e| match e {
+            Event::ExtraLife { lives } => Some(lives),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Ticks until `points` more are scored, Pac-Man eats his way left from the
+/// start.
+fn score(game: &mut Pacman, points: u32) {
+    let target = game.stats().score + points;
+    while game.stats().score < target {
+        game.tick();
+    }
+}
+
+#[test]
+fn one_extra_life_by_default() {
+    let rules = Rules {
+        extra_life: 20,
+        ..Rules::default()
+    };
+    let mut game = Pacman::with_rules(Map::new(), 42, rules);
+    let lives = game.stats().lives;
+    score(&mut game, 10);
+    assert_eq!(extra_lives(&mut game), vec![]);
+    score(&mut game, 10);
+    assert_eq!(extra_lives(&mut game), vec![lives + 1]);
+    score(&mut game, 50);
+    assert_eq!(extra_lives(&mut game), vec![]);
+    assert_eq!(game.stats().lives, lives + 1);
+}
+
+#[test]
+fn repeats_up_to_the_max() {
+    let rules = Rules {
+        extra_life: 10,
+        extra_life_every: 20,
+        max_lives: 7,
+        ..Rules::default()
+    };
+    let mut game = Pacman::with_rules(Map::new(), 42, rules);
+    let lives = game.stats().lives;
+    score(&mut game, 10);
+    assert_eq!(extra_lives(&mut game), vec![lives + 1]);
+    score(&mut game, 10);
+    assert_eq!(extra_lives(&mut game), vec![]);
+    score(&mut game, 10);
+    assert_eq!(extra_lives(&mut game), vec![lives + 2]);
+    score(&mut game, 20);
+    assert_eq!(extra_lives(&mut game), vec![]);
+    assert_eq!(game.stats().lives, 7);
+}
+
+#[test]
+fn none_when_disabled() {
+    let rules = Rules {
+        extra_life: 0,
+        ..Rules::default()
+    };
+    let mut game = Pacman::with_rules(Map::new(), 42, rules);
+    let lives = game.stats().lives;
+    score(&mut game, 50);
+    assert_eq!(extra_lives(&mut game), vec![]);
+    assert_eq!(game.stats().lives, lives);
+}
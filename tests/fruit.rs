@@ -0,0 +1,34 @@
+use rust_gaem::core::map::Map;
+use rust_gaem::core::{Pacman, TICKS_PER_SECOND};
+
+/// Pac-Man heads left along a row of 70 dots with a power up at the end, the
+/// ghosts are shut in above the house.
+fn maze() -> String {
+    let width = 77;
+    let wall = "#".repeat(width);
+    let corridor = format!("#X{}P..F#", ".".repeat(70));
+    let pocket = format!("# {}", "#".repeat(width - 2));
+    let door = format!("#-{}", "#".repeat(width - 2));
+    let house = format!("#0123{}", "#".repeat(width - 5));
+    [&wall, &corridor, &wall, &pocket, &door, &house, &wall]
+        .iter()
+        .fold(String::new(), |maze, row| maze + row + "\n")
+}
+
+#[test]
+fn power_ups_count_towards_fruit() {
+    let mut game = Pacman::with_map(Map::parse(&maze()).unwrap(), 42);
+    let mut shown = None;
+    for _ in 0..TICKS_PER_SECOND * 30 {
+        game.tick();
+        match (shown, game.fruit()) {
+            (None, Some(_)) => shown = Some(game.ticks()),
+            (Some(from), None) => {
+                assert_eq!(game.ticks() - from, TICKS_PER_SECOND * 19 / 2);
+                return;
+            }
+            _ => (),
+        }
+    }
+    panic!("fruit shown from {:?} and never went away", shown);
+}
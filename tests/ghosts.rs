@@ -0,0 +1,640 @@
+use rust_gaem::core::ghost::{DotCounter, GhostMode, GhostState, NextOut};
+use rust_gaem::core::level::Levels;
+use rust_gaem::core::map::Map;
+use rust_gaem::core::{Direction, Event, GamePhase, Pacman, Rules, SUBTILE, TICKS_PER_SECOND};
+
+/// A corridor closed at both ends for the ghosts, with Pac-Man waiting in a
+/// separate one below.
+const CORRIDOR: &str = "\
+###########
+#         #
+#####-#####
+####H0123##
+###########
+#.P.......#
+###########
+";
+
+#[test]
+fn ghosts_turn_back_at_dead_ends() {
+    let mut game = Pacman::with_map(Map::parse(CORRIDOR).unwrap(), 42);
+    let mut last = vec![(0, 0); game.ghosts().len()];
+    let mut still = vec![0; game.ghosts().len()];
+    let mut left_house = vec![false; game.ghosts().len()];
+    for _ in 0..TICKS_PER_SECOND * 60 {
+        game.tick();
+        for (i, ghost) in game.ghosts().iter().enumerate() {
+            if game.map().is_house(ghost.x(), ghost.y()) {
+                continue;
+            }
+            left_house[i] = true;
+            if (ghost.x(), ghost.y()) == last[i] {
+                still[i] += 1;
+                assert!(
+                    still[i] < TICKS_PER_SECOND,
+                    "ghost {} stuck at {:?}",
+                    i,
+                    last[i]
+                );
+            } else {
+                last[i] = (ghost.x(), ghost.y());
+                still[i] = 0;
+            }
+        }
+    }
+    assert_eq!(left_house, vec![true; game.ghosts().len()]);
+}
+
+/// Pac-Man tucked away from the ghosts, with a power up at the end of each of
+/// his two corridors.
+const POWER_UPS: &str = "\
+###########
+#         #
+#####-#####
+####H0123##
+###########
+#P.......X#
+#.#######.#
+#X........#
+###########
+";
+
+/// Pac-Man in a pocket off the ghosts' corridor, behind a power up.
+const POCKET: &str = "\
+###########
+#.........#
+#X###-#####
+#P##H0123##
+###########
+";
+
+/// The arcade's first level, as a whole table.
+const LEVEL_ONE: &str = "\
+[level 1]
+phases = scatter 7, chase
+fright = 6
+flashes = 5
+fruit = cherry
+pacman = 80
+pacman_dots = 71
+pacman_fright = 90
+pacman_fright_dots = 79
+ghost = 75
+ghost_fright = 50
+ghost_tunnel = 40
+ghost_elroy1 = 80
+ghost_elroy2 = 85
+house_dots = 0, 0, 30, 60
+house_timeout = 4
+elroy1_dots = 20
+elroy2_dots = 10
+";
+
+/// Only Blinky ever leaves the house.
+const BLINKY_ONLY: &str = "house_dots = 0, 1000, 1000, 1000\nhouse_timeout = 1000\n";
+
+/// `LEVEL_ONE` with `changes`, more `key = value` lines, for every level.
+fn level_one(changes: &str) -> Levels {
+    Levels::parse(&format!("{}{}", LEVEL_ONE, changes)).unwrap()
+}
+
+/// Ticks `game` heading `direction` until `done`.
+fn walk(game: &mut Pacman, direction: Direction, mut done: impl FnMut(&Pacman) -> bool) {
+    game.set_direction_intent(direction);
+    for _ in 0..TICKS_PER_SECOND * 60 {
+        if done(game) {
+            return;
+        }
+        game.tick();
+    }
+    panic!("never got there, at {:?}", game.player());
+}
+
+/// Ticks `game` until play starts, or resumes.
+fn play(game: &mut Pacman) {
+    for _ in 0..TICKS_PER_SECOND * 10 {
+        if game.phase() == GamePhase::Playing {
+            return;
+        }
+        game.tick();
+    }
+    panic!("still {:?}", game.phase());
+}
+
+fn out_of_house(game: &Pacman, ghost: usize) -> bool {
+    let g = &game.ghosts()[ghost];
+    !game.map().is_house(g.x(), g.y())
+}
+
+/// A game on `POWER_UPS` where Pac-Man waits for every ghost to leave the house
+/// and then eats the first power up.
+fn frightened() -> Pacman {
+    frightened_through(Rules::default(), Levels::default())
+}
+
+fn frightened_through(rules: Rules, levels: Levels) -> Pacman {
+    let map = Map::parse(POWER_UPS).unwrap();
+    let mut game = Pacman::with_levels(map, 42, rules, levels);
+    walk(&mut game, Direction::Left, |game| {
+        (0..game.ghosts().len()).all(|i| out_of_house(game, i))
+    });
+    walk(&mut game, Direction::Right, |game| game.player().0 == 9);
+    assert_eq!(game.ghost_mode(), GhostMode::Frightened);
+    game
+}
+
+/// Has Pac-Man eat the second power up on `POWER_UPS`.
+fn second_power_up(game: &mut Pacman) {
+    walk(game, Direction::Down, |game| game.player().1 == 7);
+    walk(game, Direction::Left, |game| game.player().0 == 1);
+}
+
+/// `game` saved and loaded back with `edit` applied to the fields of each
+/// record, along with the index of the record among those of the same name.
+/// The level tables saved along are left alone.
+fn edited(game: &Pacman, mut edit: impl FnMut(&str, usize, &mut Vec<String>)) -> Pacman {
+    let mut save = vec![];
+    game.save(&mut save).unwrap();
+    let mut seen = vec![];
+    let mut out = String::new();
+    let save = String::from_utf8(save).unwrap();
+    let mut lines = save.lines();
+    while let Some(line) = lines.next() {
+        if let Some(n) = line.strip_prefix("levels ") {
+            out.push_str(line);
+            out.push('\n');
+            for line in lines.by_ref().take(n.parse().unwrap()) {
+                out.push_str(line);
+                out.push('\n');
+            }
+            continue;
+        }
+        let mut fields: Vec<String> = line.split(' ').map(String::from).collect();
+        let name = fields.remove(0);
+        let i = seen.iter().filter(|&n| *n == name).count();
+        edit(&name, i, &mut fields);
+        out.push_str(&name);
+        for field in fields.iter() {
+            out.push(' ');
+            out.push_str(field);
+        }
+        out.push('\n');
+        seen.push(name);
+    }
+    Pacman::load(out.as_bytes()).unwrap()
+}
+
+/// The `n`th field of the saved `record`.
+fn field(game: &Pacman, record: &str, n: usize) -> String {
+    let mut field = None;
+    edited(game, |name, _, fields| {
+        if name == record {
+            field = Some(fields[n].clone());
+        }
+    });
+    field.unwrap()
+}
+
+/// `game` with `chain` ghosts already eaten on the current power up.
+fn with_chain(game: &Pacman, chain: u8) -> Pacman {
+    edited(game, |name, _, fields| {
+        if name == "ghosts" {
+            fields[5] = chain.to_string();
+        }
+    })
+}
+
+/// `game` with the `ghosts` out of the house, standing on `at`, just come from
+/// `from`.
+fn placed(game: &Pacman, ghosts: &[usize], at: (i32, i32), from: (i32, i32)) -> Pacman {
+    edited(game, |name, i, fields| {
+        if name == "ghost" && ghosts.contains(&i) {
+            let values = [at.0, at.1, at.0, at.1, 0, from.0, from.1];
+            for (field, v) in fields.iter_mut().zip(&values) {
+                *field = v.to_string();
+            }
+            if let "in-house" | "leaving-house" = fields[7].as_str() {
+                fields[7] = "normal".to_string();
+            }
+        }
+    })
+}
+
+/// `game` with the `ghosts` standing still on Pac-Man, who stands still too.
+fn on_player(game: &Pacman, ghosts: &[usize]) -> Pacman {
+    let (x, y, _) = game.player();
+    placed(game, ghosts, (x, y), (x, y))
+}
+
+/// `game` in the first scatter phase for another `ticks`.
+fn scattering(game: &Pacman, ticks: u32) -> Pacman {
+    edited(game, |name, _, fields| {
+        if name == "ghosts" {
+            fields[1] = "scatter".to_string();
+            fields[2] = "0".to_string();
+            fields[3] = ticks.to_string();
+        }
+    })
+}
+
+fn ghosts_eaten(game: &mut Pacman) -> Vec<Event> {
+    game.tick();
+    game.take_events()
+        .into_iter()
+        .filter(|e| !matches!(e, Event::FruitEaten { .. }))
+        .collect()
+}
+
+#[test]
+fn ghosts_eaten_on_one_power_up_score_more_and_more() {
+    let mut game = on_player(&frightened(), &[0, 1, 2, 3]);
+    let (x, y, _) = game.player();
+    let score = game.stats().score;
+    let events = ghosts_eaten(&mut game);
+    let eaten: Vec<_> = events
+        .iter()
+        .filter_map(|e| match *e {
+            Event::GhostEaten { pos, chain, points } => Some((pos, chain, points)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        eaten,
+        vec![
+            ((x, y), 1, 200),
+            ((x, y), 2, 400),
+            ((x, y), 3, 800),
+            ((x, y), 4, 1600),
+        ]
+    );
+    let bonus = events.iter().find_map(|e| match *e {
+        Event::AllGhostsEaten { total, points } => Some((total, points)),
+        _ => None,
+    });
+    let (total, points) = bonus.expect("no AllGhostsEaten event");
+    assert_eq!(total, 3000);
+    assert_eq!(game.stats().score - score, total + points);
+}
+
+#[test]
+fn chain_starts_over_on_a_new_power_up() {
+    let mut game = with_chain(&frightened(), 3);
+    second_power_up(&mut game);
+    let mut game = on_player(&game, &[0]);
+    assert!(matches!(
+        ghosts_eaten(&mut game)[..],
+        [Event::GhostEaten {
+            chain: 1,
+            points: 200,
+            ..
+        }]
+    ));
+}
+
+#[test]
+fn chain_starts_over_when_fright_ends() {
+    let mut game = with_chain(&frightened(), 3);
+    while game.ghost_mode() == GhostMode::Frightened {
+        assert_eq!(field(&game, "ghosts", 5), "3");
+        game.tick();
+    }
+    assert_eq!(field(&game, "ghosts", 5), "0");
+}
+
+#[test]
+fn eaten_ghosts_go_home_as_eyes_and_come_back_out() {
+    let map = Map::parse(POCKET).unwrap();
+    let mut game = Pacman::with_levels(map, 42, Rules::default(), level_one(BLINKY_ONLY));
+    walk(&mut game, Direction::Left, |game| {
+        game.ghosts()[0].state() == GhostState::Normal
+    });
+    walk(&mut game, Direction::Up, |game| game.player().1 == 1);
+    let mut game = on_player(&game, &[0]);
+    game.tick();
+    assert_eq!(game.ghosts()[0].state(), GhostState::Eaten);
+
+    // the arcade's 200% of full speed, in sub-tile units per tick
+    let eyes = (75.757_576 / 8.0 * SUBTILE as f64 * 2.0 / TICKS_PER_SECOND as f64).round();
+    let mut trip = 0;
+    while game.ghosts()[0].state() == GhostState::Eaten {
+        let (x, y) = game.ghosts()[0].position();
+        game.tick();
+        let (nx, ny) = game.ghosts()[0].position();
+        if game.ghosts()[0].state() == GhostState::Eaten {
+            let moved = ((nx - x).abs() + (ny - y).abs()) * SUBTILE as f64;
+            assert!((moved - eyes).abs() < 1e-6, "eyes moved {} units", moved);
+        }
+        trip += 1;
+        assert!(trip < TICKS_PER_SECOND * 5, "eyes never got home");
+    }
+    let ghost = &game.ghosts()[0];
+    assert!(game.map().is_house(ghost.x(), ghost.y()));
+    assert_eq!(ghost.state(), GhostState::LeavingHouse);
+    assert_eq!(game.ghost_mode(), GhostMode::Frightened);
+
+    // and comes back out no longer frightened
+    walk(&mut game, Direction::Up, |game| out_of_house(game, 0));
+    assert_eq!(game.ghosts()[0].state(), GhostState::LeavingHouse);
+    walk(&mut game, Direction::Up, |game| {
+        game.ghosts()[0].state() != GhostState::LeavingHouse
+    });
+    assert_eq!(game.ghosts()[0].state(), GhostState::Normal);
+}
+
+/// An open room, big enough for the ghosts to pick between any two turns.
+const ROOM: &str = "\
+###########
+#.........#
+#.........#
+#P........#
+#.........#
+#.........#
+#.........#
+#####-#####
+####H0123##
+###########
+";
+
+fn room(arcade_ghosts: bool) -> Pacman {
+    let rules = Rules {
+        arcade_ghosts,
+        ..Rules::default()
+    };
+    let mut game = Pacman::with_rules(Map::parse(ROOM).unwrap(), 42, rules);
+    play(&mut game);
+    game
+}
+
+/// Where each ghost heads off to from a tile where two turns are equally
+/// close to its scatter target.
+fn ties(arcade_ghosts: bool) -> Vec<Option<Direction>> {
+    let game = scattering(&room(arcade_ghosts), 600);
+    // the turns on either side of the line from each tile to the target are
+    // as close as each other: up and right for Blinky, up and left for
+    // Pinky, down and right for Inky and left and down for Clyde
+    let game = placed(&game, &[0], (4, 2), (4, 3));
+    let game = placed(&game, &[1], (6, 2), (6, 3));
+    let mut game = placed(&game, &[2, 3], (5, 5), (5, 4));
+    game.tick();
+    game.ghosts().iter().map(|g| g.direction()).collect()
+}
+
+#[test]
+fn arcade_ghosts_break_ties_up_left_down_right() {
+    use Direction::*;
+    assert_eq!(ties(true), vec![Some(Up), Some(Up), Some(Down), Some(Left)]);
+    assert_eq!(
+        ties(false),
+        vec![Some(Right), Some(Left), Some(Right), Some(Left)]
+    );
+}
+
+/// Where Blinky heads off to from `(x, y)` after coming from the left.
+fn turn_at(arcade_ghosts: bool, x: i32, y: i32) -> Option<Direction> {
+    let game = scattering(&room(arcade_ghosts), 600);
+    let mut game = placed(&game, &[0], (x, y), (x - 1, y));
+    game.tick();
+    game.ghosts()[0].direction()
+}
+
+#[test]
+fn arcade_ghosts_dont_turn_up_above_the_house_and_the_player() {
+    let map = Map::parse(ROOM).unwrap();
+    let (exit, start) = (map.house_exit(), map.player_start());
+    for &y in &[exit.1, start.1] {
+        for x in exit.0 - 2..=exit.0 + 3 {
+            let no_up = (exit.0 - 1..=exit.0 + 2).contains(&x);
+            let up = turn_at(true, x, y) == Some(Direction::Up);
+            assert_eq!(up, !no_up, "at {:?}", (x, y));
+            if no_up {
+                assert_eq!(turn_at(false, x, y), Some(Direction::Up), "at {:?}", (x, y));
+            }
+        }
+    }
+}
+
+/// Blinky's direction right after the mode changes, and once it next picks a
+/// way to go.
+fn after_mode_change(arcade_ghosts: bool) -> (Option<Direction>, Option<Direction>) {
+    let game = scattering(&room(arcade_ghosts), 1);
+    let mut game = placed(&game, &[0], (3, 4), (2, 4));
+    game.tick();
+    assert_eq!(game.ghost_mode(), GhostMode::Chase);
+    let before = game.ghosts()[0].direction();
+    for _ in 0..TICKS_PER_SECOND {
+        game.tick();
+        let now = game.ghosts()[0].direction();
+        if now != before {
+            return (before, now);
+        }
+    }
+    panic!("Blinky never turned");
+}
+
+#[test]
+fn arcade_ghosts_turn_around_when_the_mode_changes() {
+    let (before, after) = after_mode_change(true);
+    assert_eq!(after, before.map(Direction::opposite));
+    let (before, after) = after_mode_change(false);
+    assert_ne!(after, before.map(Direction::opposite));
+}
+
+#[test]
+fn frightened_ghosts_flash_before_they_recover() {
+    // a flash is 14 ticks white then 14 blue at the arcade's 60 Hz
+    for &(tick_rate, flash) in &[(60, 14), (30, 7)] {
+        let rules = Rules {
+            tick_rate,
+            ..Rules::default()
+        };
+        // three seconds of fright ending in two flashes
+        let levels = level_one("fright = 3\nflashes = 2\n");
+        let mut game = frightened_through(rules, levels);
+        let mut white = vec![];
+        while game.ghost_mode() == GhostMode::Frightened {
+            white.push(game.fright_flash());
+            game.tick();
+        }
+        // counting the tick the power up was eaten on and the one they recover on
+        assert_eq!(white.len() as u32 + 1, 3 * tick_rate, "at {} Hz", tick_rate);
+        let (steady, flashing) = white.split_at(white.len() - 4 * flash);
+        assert!(steady.iter().all(|w| !w), "at {} Hz", tick_rate);
+        let expected: Vec<_> = [true, false, true, false]
+            .iter()
+            .flat_map(|&w| vec![w; flash])
+            .collect();
+        assert_eq!(flashing, &expected[..], "at {} Hz", tick_rate);
+    }
+}
+
+#[test]
+fn ghosts_in_the_house_or_leaving_it_arent_frightened() {
+    let map = Map::parse(POCKET).unwrap();
+    let game = Pacman::with_levels(map, 42, Rules::default(), level_one(BLINKY_ONLY));
+    // Blinky on the way out, the others still waiting
+    let mut game = edited(&game, |name, i, fields| {
+        if name == "ghost" && i == 0 {
+            fields[7] = "leaving-house".to_string();
+        }
+    });
+    walk(&mut game, Direction::Up, |game| {
+        game.ghost_mode() == GhostMode::Frightened
+    });
+    let states: Vec<_> = game.ghosts().iter().map(|g| g.state()).collect();
+    assert_eq!(
+        states,
+        vec![
+            GhostState::LeavingHouse,
+            GhostState::InHouse,
+            GhostState::InHouse,
+            GhostState::InHouse,
+        ]
+    );
+    walk(&mut game, Direction::Up, |game| {
+        game.ghosts()[0].state() != GhostState::LeavingHouse
+    });
+    assert_eq!(game.ghosts()[0].state(), GhostState::Normal);
+    assert_eq!(game.ghost_mode(), GhostMode::Frightened);
+}
+
+/// A row of dots for Pac-Man, out of the ghosts' way.
+const DOTS: &str = "\
+########################################
+#                                      #
+###################-####################
+##################H0123#################
+########################################
+#P.....................................#
+########################################
+";
+
+fn states(game: &Pacman) -> Vec<GhostState> {
+    game.ghosts().iter().map(|g| g.state()).collect()
+}
+
+/// Dots eaten so far by Pac-Man walking right along `DOTS`, when each ghost
+/// was let out of the house.
+fn let_out_at(game: &mut Pacman) -> Vec<u32> {
+    let mut out = vec![None; game.ghosts().len()];
+    walk(game, Direction::Right, |game| {
+        for (i, state) in states(game).into_iter().enumerate() {
+            if out[i].is_none() && state != GhostState::InHouse {
+                out[i] = Some(game.stats().score / 10);
+            }
+        }
+        out.iter().all(Option::is_some)
+    });
+    out.into_iter().map(Option::unwrap).collect()
+}
+
+#[test]
+fn ghosts_leave_the_house_on_their_own_dot_counts() {
+    let map = Map::parse(DOTS).unwrap();
+    let levels = level_one("house_dots = 0, 3, 5, 10\n");
+    let mut game = Pacman::with_levels(map, 42, Rules::default(), levels);
+    play(&mut game);
+    game.tick();
+    assert_eq!(
+        game.next_out(),
+        Some(NextOut {
+            ghost: 1,
+            counter: DotCounter::Personal,
+            dots: 0,
+            limit: 3,
+            idle: 1,
+            timeout: 240,
+        })
+    );
+    // each waits for its own dots once it's next in line
+    assert_eq!(let_out_at(&mut game), vec![0, 3, 8, 18]);
+    assert_eq!(game.next_out(), None);
+}
+
+/// `game` after Pac-Man is caught where he stands, once play resumes.
+fn caught(game: &Pacman) -> Pacman {
+    let lives = game.stats().lives;
+    let mut game = on_player(game, &[0]);
+    play(&mut game);
+    game.tick();
+    assert_eq!(game.phase(), GamePhase::Dying);
+    assert_eq!(game.stats().lives, lives - 1);
+    play(&mut game);
+    assert_eq!(states(&game), vec![GhostState::InHouse; 4]);
+    game
+}
+
+#[test]
+fn ghosts_leave_on_the_global_counter_after_a_life_is_lost() {
+    let mut game = caught(&Pacman::with_map(Map::parse(DOTS).unwrap(), 42));
+    assert_eq!(field(&game, "house", 0), "global");
+    assert_eq!(let_out_at(&mut game), vec![0, 7, 17, 32]);
+    // and it's back to each ghost's own count once the last one is out
+    assert_eq!(field(&game, "house", 0), "personal");
+}
+
+#[test]
+fn ghosts_leave_anyway_when_no_dot_is_eaten_for_a_while() {
+    let map = Map::parse(DOTS).unwrap();
+    let levels = level_one("house_timeout = 2\n");
+    let mut game = caught(&Pacman::with_levels(map, 42, Rules::default(), levels));
+    // Pac-Man stays put against the wall
+    let mut out = vec![];
+    for tick in 1..=TICKS_PER_SECOND * 7 {
+        let before = states(&game);
+        game.tick();
+        for (i, (was, is)) in before.iter().zip(states(&game)).enumerate() {
+            if *was == GhostState::InHouse && is != GhostState::InHouse {
+                out.push((i, tick));
+            }
+        }
+    }
+    let timeout = 2 * TICKS_PER_SECOND;
+    assert_eq!(
+        out,
+        vec![(0, 1), (1, timeout), (2, 2 * timeout), (3, 3 * timeout)]
+    );
+    // the last one out on the timeout is done with the global counter too
+    assert_eq!(field(&game, "house", 0), "personal");
+}
+
+/// `DOTS` with a power up for the 11th pellet.
+const ELROY: &str = "\
+########################################
+#                                      #
+###################-####################
+##################H0123#################
+########################################
+#P..........X..........................#
+########################################
+";
+
+#[test]
+fn blinky_becomes_cruise_elroy_as_pellets_run_out() {
+    let map = Map::parse(ELROY).unwrap();
+    let levels = level_one("elroy1_dots = 26\nelroy2_dots = 10\n");
+    let mut game = Pacman::with_levels(map, 42, Rules::default(), levels);
+    let mut stages = vec![(game.map().pellets(), game.elroy())];
+    walk(&mut game, Direction::Right, |game| {
+        let stage = game.elroy();
+        if stage != stages.last().unwrap().1 {
+            stages.push((game.map().pellets(), stage));
+        }
+        game.map().pellets() == 1
+    });
+    // eating the power up counts towards the first stage
+    assert_eq!(stages, vec![(37, 0), (26, 1), (10, 2)]);
+}
+
+#[test]
+fn cruise_elroy_waits_for_the_last_ghost_out_after_a_life_is_lost() {
+    let map = Map::parse(DOTS).unwrap();
+    let levels = level_one("elroy1_dots = 100\nelroy2_dots = 0\n");
+    let game = Pacman::with_levels(map, 42, Rules::default(), levels);
+    assert_eq!(game.elroy(), 1);
+    let mut game = caught(&game);
+    assert_eq!(game.elroy(), 0);
+    walk(&mut game, Direction::Right, |game| game.elroy() != 0);
+    // back as Clyde is let out on the global counter
+    assert_eq!(game.stats().score / 10, 32);
+    assert_eq!(states(&game)[3], GhostState::LeavingHouse);
+}
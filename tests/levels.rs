@@ -0,0 +1,154 @@
+use rust_gaem::core::fruit::Fruit;
+use rust_gaem::core::ghost::GhostMode;
+use rust_gaem::core::level::{Levels, LevelsError, Phase};
+use rust_gaem::core::map::Map;
+use rust_gaem::core::{GamePhase, Pacman, Rules};
+
+const FIRST: &str = "\
+[level 1]
+phases = scatter 7, chase
+fright = 6
+flashes = 5
+fruit = cherry
+pacman = 80
+pacman_dots = 71
+pacman_fright = 90
+pacman_fright_dots = 79
+ghost = 75
+ghost_fright = 50
+ghost_tunnel = 40
+ghost_elroy1 = 80
+ghost_elroy2 = 85
+house_dots = 0, 0, 30, 60
+house_timeout = 4
+elroy1_dots = 20
+elroy2_dots = 10
+";
+
+fn invalid(src: &str) -> (usize, String) {
+    match Levels::parse(src) {
+        Err(LevelsError::Invalid { line, msg }) => (line, msg),
+        Err(e) => panic!("unexpected error: {}", e),
+        Ok(_) => panic!("levels should be rejected"),
+    }
+}
+
+#[test]
+fn levels_start_as_copies_of_the_one_before() {
+    let src = format!("{}\n[level 3]\nfright = 2\nfruit = orange\n", FIRST);
+    let levels = Levels::parse(&src).unwrap();
+    let (first, second, third) = (levels.get(1), levels.get(2), levels.get(3));
+    assert_eq!(first.fright, 6.0);
+    assert_eq!(second, first);
+    assert_eq!(third.fright, 2.0);
+    assert_eq!(third.fruit, Fruit::Orange);
+    assert_eq!(third.speeds, first.speeds);
+    assert_eq!(third.house_dots, vec![0, 0, 30, 60]);
+}
+
+#[test]
+fn levels_past_the_last_play_like_it() {
+    let src = format!("{}\n[level 2]\nghost = 85\n", FIRST);
+    let levels = Levels::parse(&src).unwrap();
+    assert_eq!(levels.get(2).speeds.ghost, 85);
+    assert_eq!(levels.get(50), levels.get(2));
+    // and there's no level 0
+    assert_eq!(levels.get(0), levels.get(1));
+}
+
+#[test]
+fn phases_are_in_seconds() {
+    let levels = Levels::parse(FIRST).unwrap();
+    assert_eq!(
+        levels.get(1).phases,
+        vec![
+            Phase {
+                mode: GhostMode::Scatter,
+                seconds: Some(7.0)
+            },
+            Phase {
+                mode: GhostMode::Chase,
+                seconds: None
+            },
+        ]
+    );
+}
+
+#[test]
+fn phases_last_as_long_at_any_tick_rate() {
+    let src = FIRST.replace("scatter 7, chase", "scatter 2.5, chase");
+    for &rate in &[30, 50, 60] {
+        let rules = Rules {
+            tick_rate: rate,
+            ..Rules::default()
+        };
+        let levels = Levels::parse(&src).unwrap();
+        let mut game = Pacman::with_levels(Map::new(), 1, rules, levels);
+        while game.phase() != GamePhase::Playing {
+            game.tick();
+        }
+        let mut ticks = 0;
+        while game.ghost_mode() == GhostMode::Scatter {
+            game.tick();
+            ticks += 1;
+        }
+        assert_eq!(ticks, (2.5 * rate as f64).round() as u32, "at {} Hz", rate);
+    }
+}
+
+#[test]
+fn level_one_has_to_set_every_key() {
+    let src = FIRST.replace("ghost_tunnel = 40\n", "");
+    assert_eq!(
+        invalid(&src),
+        (1, "level 1 has no ghost_tunnel".to_string())
+    );
+}
+
+#[test]
+fn bad_phases() {
+    let src = FIRST.replace("scatter 7, chase", "chase, scatter 7");
+    let (line, msg) = invalid(&src);
+    assert_eq!(line, 2);
+    assert!(msg.starts_with("only the last phase"), "{}", msg);
+
+    let src = FIRST.replace("scatter 7, chase", "scatter 7, frightened");
+    let (line, msg) = invalid(&src);
+    assert_eq!(line, 2);
+    assert!(msg.starts_with("expected scatter or chase"), "{}", msg);
+
+    let src = FIRST.replace("scatter 7, chase", "scatter -1, chase");
+    assert_eq!(invalid(&src), (2, "bad time: -1".to_string()));
+}
+
+#[test]
+fn levels_in_order_from_one() {
+    let src = format!("{}\n[level 3]\n[level 2]\n", FIRST);
+    assert_eq!(invalid(&src), (21, "level 2 after level 3".to_string()));
+    let src = FIRST.replace("[level 1]", "[level 2]");
+    assert_eq!(
+        invalid(&src),
+        (1, "the first level has to be level 1".to_string())
+    );
+}
+
+#[test]
+fn arcade_levels() {
+    let levels = Levels::default();
+    let first = levels.get(1);
+    assert_eq!(first.fright, 6.0);
+    assert_eq!(first.speeds.pacman, 80);
+    assert_eq!(first.phases.len(), 8);
+    assert_eq!(first.house_dots, vec![0, 0, 30, 60]);
+    assert_eq!(first.house_timeout, 4.0);
+    assert_eq!(levels.get(3).house_dots, vec![0, 0, 0, 0]);
+    assert_eq!(levels.get(5).house_timeout, 3.0);
+    assert_eq!(levels.get(5).speeds.ghost_elroy2, 105);
+    assert_eq!(levels.get(21).elroy1_dots, 120);
+    assert_eq!(levels.get(5).speeds.ghost, 95);
+    assert_eq!(levels.get(8).fruit, Fruit::Melon);
+    assert_eq!(levels.get(13).fruit, Fruit::Key);
+    assert_eq!(levels.get(17).fright, 0.0);
+    assert_eq!(levels.get(20).speeds.pacman, 100);
+    assert_eq!(levels.get(21).speeds.pacman, 90);
+}
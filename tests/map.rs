@@ -0,0 +1,166 @@
+use rust_gaem::core::ghost::Personality;
+use rust_gaem::core::map::{Map, MapError, Tile, PU};
+
+const MAZE: &str = "\
+###########
+#X.......X#
+#.###-###.#
+#.#H0123#.#
+#.#######.#
+#....P....#
+###########
+";
+
+/// `MAZE` with `row` swapped for `line`.
+fn with(row: usize, line: &str) -> String {
+    MAZE.lines()
+        .enumerate()
+        .map(|(i, l)| if i == row { line } else { l })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn errors(maze: &str) -> Vec<MapError> {
+    Map::parse(maze).err().expect("maze should be rejected")
+}
+
+#[test]
+fn accepts_a_good_maze() {
+    assert_eq!(Map::parse(MAZE).map(|_| ()), Ok(()));
+}
+
+#[test]
+fn ragged_row() {
+    assert_eq!(
+        errors(&with(4, "#.#######.")),
+        vec![MapError::RaggedRow {
+            row: 4,
+            len: 10,
+            expected: 11
+        }]
+    );
+}
+
+#[test]
+fn unknown_glyph() {
+    let errors = errors(&with(1, "#X.?.....X#"));
+    assert_eq!(
+        errors,
+        vec![MapError::UnknownGlyph {
+            row: 1,
+            col: 3,
+            glyph: '?'
+        }]
+    );
+    assert_eq!(errors[0].to_string(), "2:4: unknown glyph '?'");
+}
+
+#[test]
+fn unreachable_pellet() {
+    assert_eq!(
+        errors(&with(3, "#.#.0123#.#")),
+        vec![MapError::UnreachablePellet { row: 3, col: 3 }]
+    );
+}
+
+#[test]
+fn unmatched_tunnel() {
+    assert_eq!(
+        errors(&with(5, "=....P....#")),
+        vec![MapError::UnmatchedTunnel { row: 5, col: 0 }]
+    );
+}
+
+#[test]
+fn house_without_exit() {
+    assert_eq!(
+        errors(&with(3, "#.#0#123#.#")),
+        vec![MapError::HouseWithoutExit { row: 3, col: 3 }]
+    );
+}
+
+#[test]
+fn duplicate_player_start() {
+    assert_eq!(
+        errors(&with(5, "#..P.P....#")),
+        vec![MapError::DuplicatePlayerStart { row: 5, col: 5 }]
+    );
+}
+
+#[test]
+fn missing_player_start() {
+    assert_eq!(
+        errors(&with(5, "#.........#")),
+        vec![MapError::MissingPlayerStart]
+    );
+}
+
+#[test]
+fn duplicate_ghost_start() {
+    assert_eq!(
+        errors(&with(3, "#.#00123#.#")),
+        vec![MapError::DuplicateGhostStart {
+            row: 3,
+            col: 4,
+            ghost: 0
+        }]
+    );
+}
+
+#[test]
+fn missing_ghost_start() {
+    assert_eq!(
+        errors(&with(3, "#.#H0H23#.#")),
+        vec![MapError::MissingGhostStart(1)]
+    );
+}
+
+#[test]
+fn player_start_boxed_in() {
+    assert!(
+        errors(&with(5, "#...#P#...#")).contains(&MapError::PlayerStartBoxedIn { row: 5, col: 5 })
+    );
+}
+
+#[test]
+fn reset_puts_back_dots_and_power_ups() {
+    let mut map = Map::parse(MAZE).unwrap();
+    let pellets = map.pellets();
+    assert_eq!(pellets, 23);
+    map.consume(1, 1);
+    map.consume(2, 1);
+    assert_eq!((map.pellets(), map.eaten()), (pellets - 2, 2));
+    map.reset();
+    assert_eq!((map.pellets(), map.eaten()), (pellets, 0));
+    assert_eq!(map.get(1, 1), Some(Tile::NotWall(PU::PowerUp)));
+    assert_eq!(map.get(2, 1), Some(Tile::NotWall(PU::Dot)));
+}
+
+#[test]
+fn roster_changes_ghosts() {
+    let src = format!(
+        "{}\n[ghost 2]\nname = sue\npersonality = clyde\ncorner = -2, 14\ncolor = a040ff\n",
+        MAZE
+    );
+    let map = Map::parse(&src).unwrap();
+    let sue = &map.roster()[2];
+    assert_eq!(sue.name, "sue");
+    assert_eq!(sue.personality, Personality::Clyde);
+    assert_eq!(sue.corner, (-2, 14));
+    assert_eq!(sue.color, [0xa0, 0x40, 0xff]);
+    // the others stay classic
+    assert_eq!(map.roster()[0].personality, Personality::Blinky);
+}
+
+#[test]
+fn roster_colors_have_no_hash() {
+    // it starts a comment, leaving no color at all
+    let src = format!("{}\n[ghost 0]\ncolor = #a040ff\n", MAZE);
+    assert_eq!(
+        errors(&src),
+        vec![MapError::BadRoster {
+            row: 9,
+            msg: "bad color: ".to_string()
+        }]
+    );
+}
@@ -0,0 +1,82 @@
+use rust_gaem::core::map::Map;
+use rust_gaem::core::{GamePhase, Pacman, Rules};
+
+/// Ticks until the game is in `phase`, failing after a simulated ten minutes.
+fn until(game: &mut Pacman, phase: GamePhase) {
+    for _ in 0..60 * 60 * 10 {
+        if game.phase() == phase {
+            return;
+        }
+        game.tick();
+    }
+    panic!("never got to {:?}, stuck in {:?}", phase, game.phase());
+}
+
+#[test]
+fn starts_ready_then_plays() {
+    let mut game = Pacman::with_map(Map::new(), 42);
+    assert_eq!(game.phase(), GamePhase::Ready);
+    let start = game.player_position();
+    until(&mut game, GamePhase::Playing);
+    assert!(game.ticks() > 1);
+    assert_eq!(game.player_position(), start);
+}
+
+#[test]
+fn dying_goes_back_to_ready_with_a_life_less() {
+    let mut game = Pacman::with_map(Map::new(), 42);
+    let lives = game.stats().lives;
+    until(&mut game, GamePhase::Dying);
+    assert_eq!(game.stats().lives, lives - 1);
+    let caught = game.player_position();
+    game.tick();
+    assert_eq!(game.player_position(), caught);
+    until(&mut game, GamePhase::Ready);
+    assert_eq!(game.player_position(), {
+        let (x, y) = game.map().player_start();
+        (x as f64, y as f64)
+    });
+}
+
+#[test]
+fn game_over_until_restarted() {
+    let mut game = Pacman::with_map(Map::new(), 42);
+    until(&mut game, GamePhase::GameOver);
+    assert_eq!(game.stats().lives, 0);
+    let score = game.stats().score;
+    game.tick();
+    assert_eq!(game.phase(), GamePhase::GameOver);
+    assert_eq!(game.stats().score, score);
+
+    game.restart();
+    let fresh = Pacman::with_map(Map::new(), 42);
+    assert_eq!(game.phase(), GamePhase::Ready);
+    assert_eq!(game.stats().score, 0);
+    assert_eq!(game.stats().lives, fresh.stats().lives);
+    assert_eq!(game.map().pellets(), fresh.map().pellets());
+}
+
+#[test]
+fn level_complete_then_next_level() {
+    let mut game = Pacman::with_map(Map::new(), 42);
+    until(&mut game, GamePhase::Playing);
+    game.level_up();
+    until(&mut game, GamePhase::LevelComplete);
+    assert_eq!(game.stats().level, 1);
+    until(&mut game, GamePhase::Ready);
+    assert_eq!(game.stats().level, 2);
+    assert_eq!(game.map().pellets(), Map::new().pellets());
+}
+
+#[test]
+fn ready_lasts_as_long_at_any_tick_rate() {
+    for &rate in &[30, 60] {
+        let rules = Rules {
+            tick_rate: rate,
+            ..Rules::default()
+        };
+        let mut game = Pacman::with_rules(Map::new(), 42, rules);
+        until(&mut game, GamePhase::Playing);
+        assert_eq!(game.ticks(), 2 * rate, "at {} Hz", rate);
+    }
+}
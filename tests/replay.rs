@@ -0,0 +1,52 @@
+use rust_gaem::core::map::Map;
+use rust_gaem::core::replay::Replay;
+use rust_gaem::core::{Direction, Pacman, Rules};
+
+fn saved(game: &Pacman) -> Vec<u8> {
+    let mut out = vec![];
+    game.save(&mut out).unwrap();
+    out
+}
+
+#[test]
+fn replay_plays_the_same_game() {
+    let rules = Rules {
+        tick_rate: 90,
+        arcade_ghosts: false,
+        extra_life: 500,
+        extra_life_every: 1000,
+        max_lives: 9,
+    };
+    let mut game = Pacman::with_rules(Map::new(), 7, rules);
+    let mut replay = Replay::new(&game);
+    let directions = [
+        Direction::Left,
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+    ];
+    for t in 0..3000 {
+        if t % 45 == 0 {
+            let direction = directions[(t / 45 + t / 7) % 4];
+            replay.record(game.ticks(), direction);
+            game.set_direction_intent(direction);
+        }
+        game.tick();
+    }
+    assert!(game.stats().score > 0);
+
+    let path = std::env::temp_dir().join(format!("pac-replay-{}", std::process::id()));
+    replay.save(&path).unwrap();
+    let replay = Replay::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let mut replayed = replay.game();
+    let mut inputs = replay.inputs().iter().peekable();
+    while replayed.ticks() < game.ticks() {
+        while let Some((_, direction)) = inputs.next_if(|(t, _)| *t <= replayed.ticks()) {
+            replayed.set_direction_intent(*direction);
+        }
+        replayed.tick();
+    }
+    assert_eq!(saved(&replayed), saved(&game));
+}
@@ -0,0 +1,63 @@
+use rust_gaem::core::map::{Map, Tile, PU};
+use rust_gaem::core::{Direction, Pacman};
+
+fn saved(game: &Pacman) -> Vec<u8> {
+    let mut out = vec![];
+    game.save(&mut out).unwrap();
+    out
+}
+
+fn steer(game: &mut Pacman) {
+    let directions = [
+        Direction::Up,
+        Direction::Left,
+        Direction::Down,
+        Direction::Right,
+    ];
+    let t = game.ticks() as usize;
+    if t.is_multiple_of(7) {
+        game.set_direction_intent(directions[(t / 7) % 4]);
+    }
+}
+
+#[test]
+fn save_load_tick_matches_tick() {
+    let mut game = Pacman::with_map(Map::new(), 42);
+    for _ in 0..600 {
+        let mut restored = Pacman::load(&saved(&game)[..]).unwrap();
+        assert_eq!(saved(&restored), saved(&game));
+        steer(&mut game);
+        steer(&mut restored);
+        game.tick();
+        restored.tick();
+        assert_eq!(saved(&restored), saved(&game), "at tick {}", game.ticks());
+    }
+}
+
+#[test]
+fn rejects_other_versions() {
+    let save = String::from_utf8(saved(&Pacman::new())).unwrap();
+    let (_, body) = save.split_once('\n').unwrap();
+    let save = format!("pac-save 0\n{}", body);
+    assert!(Pacman::load(save.as_bytes()).is_err());
+    assert!(Pacman::load(&b"not a save"[..]).is_err());
+}
+
+#[test]
+fn pellets_left_come_from_the_maze() {
+    let mut game = Pacman::with_map(Map::new(), 42);
+    for _ in 0..600 {
+        steer(&mut game);
+        game.tick();
+    }
+    let restored = Pacman::load(&saved(&game)[..]).unwrap();
+    let left = restored
+        .map()
+        .scan_lines()
+        .flatten()
+        .filter(|t| matches!(t, Tile::NotWall(PU::Dot) | Tile::NotWall(PU::PowerUp)))
+        .count();
+    assert!(restored.map().pellets() < Map::new().pellets());
+    assert_eq!(restored.map().pellets(), left as u32);
+    assert_eq!(restored.map().pellets(), game.map().pellets());
+}
@@ -0,0 +1,96 @@
+use rust_gaem::core::scores::{self, HighScore, HighScores, Initials, MAX_SCORES};
+use rust_gaem::core::Stats;
+
+fn score(initials: &str, score: u32) -> HighScore {
+    let stats = Stats {
+        lives: 0,
+        score,
+        level: 3,
+    };
+    HighScore::new(initials, stats, 42, "classic")
+}
+
+#[test]
+fn keeps_the_best_in_order() {
+    let mut table = HighScores::default();
+    assert!(table.qualifies(10));
+    assert!(!table.qualifies(0));
+    for i in 1..=MAX_SCORES as u32 {
+        assert!(table.insert(score("AAA", i * 100)).is_some());
+    }
+    assert_eq!(table.best(), 1000);
+    assert!(!table.qualifies(100));
+    assert!(table.qualifies(101));
+    // ties go below the older score
+    assert_eq!(table.insert(score("BBB", 500)), Some(6));
+    assert_eq!(table.scores().len(), MAX_SCORES);
+    assert_eq!(table.scores().last().unwrap().score, 200);
+    assert_eq!(table.insert(score("CCC", 100)), None);
+}
+
+#[test]
+fn round_trips_through_a_file() {
+    let path = std::env::temp_dir().join(format!("pac-scores-{}.txt", std::process::id()));
+    let mut table = HighScores::default();
+    table.insert(score("ABC", 1230));
+    table.insert(HighScore {
+        maze: "my maze".to_string(),
+        ..score("Z", 40)
+    });
+    table.save(&path).unwrap();
+    let loaded = HighScores::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded, table);
+    let date = &loaded.scores()[0].date;
+    assert_eq!(date.len(), 10);
+    assert_eq!(date.as_bytes()[4], b'-');
+}
+
+#[test]
+fn missing_file_is_an_empty_table() {
+    let table = HighScores::load("no/such/highscores.txt").unwrap();
+    assert_eq!(table.scores(), &[]);
+}
+
+#[test]
+fn skips_corrupt_lines() {
+    let table = HighScores::parse(
+        "pac-scores 1\n\
+         500 2 2020-01-01 7 ABC classic\n\
+         garbage\n\
+         600 x 2020-01-01 7 ABC classic\n\
+         700 2 2020-01-01 7 abcd classic\n\
+         300 1 2020-01-01 7 XY chaos\n\
+         900 4 2020-01-01 7 QQ",
+    );
+    let kept = table
+        .scores()
+        .iter()
+        .map(|s| (s.initials.as_str(), s.score))
+        .collect::<Vec<_>>();
+    assert_eq!(kept, vec![("ABC", 500), ("XY", 300)]);
+}
+
+#[test]
+fn initials_arcade_style() {
+    let mut initials = Initials::default();
+    initials.cycle(-1);
+    initials.take();
+    initials.type_letter('b');
+    initials.back();
+    assert_eq!(initials.taken(), "Z");
+    assert_eq!(initials.current(), 'B');
+    initials.cycle(2);
+    initials.take();
+    initials.type_letter('1');
+    assert!(!initials.is_done());
+    initials.type_letter('x');
+    assert!(initials.is_done());
+    assert_eq!(initials.taken(), "ZDX");
+}
+
+#[test]
+fn maze_names() {
+    assert_eq!(scores::maze_name(None), "classic");
+    assert_eq!(scores::maze_name(Some("mazes/chaos.txt")), "chaos");
+}
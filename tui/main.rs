@@ -0,0 +1,270 @@
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::style::{Color, Print, ResetColor, SetForegroundColor};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use rust_gaem::cli::Args;
+use rust_gaem::clock::FixedStep;
+use rust_gaem::core::ghost::GhostState;
+use rust_gaem::core::map::{Tile, PU};
+use rust_gaem::core::scores::{self, HighScore, HighScores, Initials};
+use rust_gaem::core::{Direction, Event as GameEvent, GamePhase, Pacman};
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+const USAGE: &str =
+    "usage: pac-tui [--tick-rate <hz>] [--seed <seed>] [--simple-ghosts] [--levels <file>]
+               [--extra-life <points>] [--extra-life-every <points>] [maze]";
+/// Most ticks run between two draws.
+const MAX_STEPS: u32 = 4;
+const HIGH_SCORES: &str = "highscores.txt";
+
+const WALL: Color = Color::Rgb {
+    r: 33,
+    g: 33,
+    b: 222,
+};
+const FRIGHTENED: Color = Color::Rgb {
+    r: 33,
+    g: 33,
+    b: 255,
+};
+
+fn main() {
+    let args = Args::parse(USAGE, |_, _| false);
+    let mut game = args.game();
+    let clock = FixedStep::new(game.tick_rate() as f64, MAX_STEPS);
+    let mut high_scores = HighScores::load(HIGH_SCORES).unwrap_or_else(|e| {
+        eprintln!("Failed to load {}: {}", HIGH_SCORES, e);
+        HighScores::default()
+    });
+    let maze = scores::maze_name(args.maze.as_deref());
+
+    let mut out = io::stdout();
+    terminal::enable_raw_mode().expect("Failed to enter raw mode");
+    execute!(out, EnterAlternateScreen, Hide).expect("Failed to set up the terminal");
+    let result = run(&mut game, &mut high_scores, &maze, clock, &mut out);
+    execute!(out, ResetColor, Show, LeaveAlternateScreen).ok();
+    terminal::disable_raw_mode().ok();
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+    eprintln!("seed: {}", game.seed());
+}
+
+fn run(
+    game: &mut Pacman,
+    high_scores: &mut HighScores,
+    maze: &str,
+    mut clock: FixedStep,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let mut paused = false;
+    // the last points scored or extra life, and for how many more ticks to
+    // show it
+    let mut popup: Option<(String, u32)> = None;
+    // initials being entered for a new high score
+    let mut initials: Option<Initials> = None;
+    // shown instead of the game over message
+    let mut notice: Option<String> = None;
+    let mut last = Instant::now();
+    let mut screen = Screen::default();
+    queue!(out, Clear(ClearType::All))?;
+    loop {
+        let status = match (&initials, game.phase()) {
+            _ if paused => "PAUSED".to_string(),
+            (Some(i), _) => format!("NEW HIGH SCORE, initials: {}{}", i.taken(), i.current()),
+            (None, GamePhase::Ready) => "READY!".to_string(),
+            (None, GamePhase::GameOver) => notice
+                .clone()
+                .unwrap_or_else(|| "GAME OVER, enter to play again".to_string()),
+            _ => String::new(),
+        };
+        let popup_text = popup.as_ref().map(|p| p.0.as_str());
+        screen.draw(game, popup_text, &status, high_scores.best(), out)?;
+
+        let timeout = Duration::from_secs_f64(clock.tick() * (1.0 - clock.alpha()));
+        if event::poll(timeout)? {
+            if let Event::Key(KeyEvent {
+                code,
+                kind: KeyEventKind::Press,
+                ..
+            }) = event::read()?
+            {
+                if let Some(i) = &mut initials {
+                    match code {
+                        KeyCode::Up => i.cycle(1),
+                        KeyCode::Down => i.cycle(-1),
+                        KeyCode::Right | KeyCode::Enter => i.take(),
+                        KeyCode::Left | KeyCode::Backspace => i.back(),
+                        KeyCode::Char(c) => i.type_letter(c),
+                        KeyCode::Esc => return Ok(()),
+                        _ => (),
+                    }
+                    if i.is_done() {
+                        high_scores.insert(HighScore::new(
+                            i.taken(),
+                            game.stats(),
+                            game.seed(),
+                            maze,
+                        ));
+                        initials = None;
+                        if let Err(e) = high_scores.save(HIGH_SCORES) {
+                            notice = Some(format!("Failed to save to {}: {}", HIGH_SCORES, e));
+                        }
+                    }
+                    continue;
+                }
+                match code {
+                    KeyCode::Up | KeyCode::Char('k') => game.set_direction_intent(Direction::Up),
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        game.set_direction_intent(Direction::Down)
+                    }
+                    KeyCode::Left | KeyCode::Char('h') => {
+                        game.set_direction_intent(Direction::Left)
+                    }
+                    KeyCode::Right | KeyCode::Char('l') => {
+                        game.set_direction_intent(Direction::Right)
+                    }
+                    KeyCode::Char('p') => paused = !paused,
+                    KeyCode::Enter | KeyCode::Char(' ') if game.phase() == GamePhase::GameOver => {
+                        game.restart();
+                        popup = None;
+                        notice = None;
+                    }
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    _ => (),
+                }
+            }
+        }
+        let now = Instant::now();
+        let steps = clock.advance((now - last).as_secs_f64());
+        last = now;
+        if !paused {
+            for _ in 0..steps {
+                let phase = game.phase();
+                game.tick();
+                if phase != GamePhase::GameOver
+                    && game.phase() == GamePhase::GameOver
+                    && high_scores.qualifies(game.stats().score)
+                {
+                    initials = Some(Initials::default());
+                }
+                popup = popup.filter(|p| p.1 > 1).map(|(text, t)| (text, t - 1));
+                for event in game.take_events() {
+                    match event {
+                        GameEvent::GhostEaten { points, .. }
+                        | GameEvent::FruitEaten { points, .. } => {
+                            popup = Some((format!("+{}", points), game.tick_rate()))
+                        }
+                        GameEvent::ExtraLife { .. } => {
+                            popup = Some(("1UP".to_string(), 2 * game.tick_rate()))
+                        }
+                        GameEvent::AllGhostsEaten { .. } => (),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Redraws only when something changed, the game ticks much faster than things
+/// move from tile to tile.
+#[derive(Default)]
+struct Screen {
+    last: Vec<u8>,
+}
+
+impl Screen {
+    fn draw(
+        &mut self,
+        game: &Pacman,
+        popup: Option<&str>,
+        status: &str,
+        best: u32,
+        out: &mut impl Write,
+    ) -> io::Result<()> {
+        let mut frame = vec![];
+        draw(game, popup, status, best, &mut frame)?;
+        if frame != self.last {
+            out.write_all(&frame)?;
+            out.flush()?;
+            self.last = frame;
+        }
+        Ok(())
+    }
+}
+
+fn draw(
+    game: &Pacman,
+    popup: Option<&str>,
+    status: &str,
+    best: u32,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let map = game.map();
+    let (px, py, _) = game.player();
+    let stats = game.stats();
+    let fruit = game.fruit().map(|(_, pos)| pos);
+    let phase = game.phase();
+    let progress = game.phase_progress();
+    let dying = phase == GamePhase::Dying && progress >= 0.5;
+    let hidden = dying || matches!(phase, GamePhase::LevelComplete | GamePhase::GameOver);
+    let flash = phase == GamePhase::LevelComplete && (progress * 8.0) as u32 % 2 == 1;
+    for (y, line) in map.scan_lines().enumerate() {
+        queue!(out, MoveTo(0, y as u16))?;
+        for (x, tile) in line.iter().enumerate() {
+            let (x, y) = (x as i32, y as i32);
+            let ghost = game
+                .ghosts()
+                .iter()
+                .find(|g| !hidden && (g.x(), g.y()) == (x, y));
+            let (color, glyph) = if let Some(g) = ghost {
+                match g.state() {
+                    GhostState::Eaten => (Color::White, "°°"),
+                    GhostState::Frightened if game.fright_flash() => (Color::White, "ᗣ "),
+                    GhostState::Frightened => (FRIGHTENED, "ᗣ "),
+                    _ => {
+                        let [r, g, b] = g.color();
+                        (Color::Rgb { r, g, b }, "ᗣ ")
+                    }
+                }
+            } else if (x, y) == (px, py) && dying {
+                (Color::Yellow, "✶ ")
+            } else if (x, y) == (px, py) && phase != GamePhase::GameOver {
+                (Color::Yellow, "ᗧ ")
+            } else if fruit == Some((x, y)) {
+                (Color::Magenta, "♣ ")
+            } else {
+                match tile {
+                    Tile::Wall if flash => (Color::White, "██"),
+                    Tile::Wall => (WALL, "██"),
+                    Tile::NotWall(PU::Dot) => (Color::White, "· "),
+                    Tile::NotWall(PU::PowerUp) => (Color::White, "● "),
+                    Tile::NotWall(PU::Empty) | Tile::House => (Color::Reset, "  "),
+                }
+            };
+            queue!(out, SetForegroundColor(color), Print(glyph))?;
+        }
+    }
+    queue!(
+        out,
+        MoveTo(0, map.height() as u16),
+        ResetColor,
+        Clear(ClearType::CurrentLine),
+        Print(format!(
+            "SCORE {:<8} HIGH {:<8} LEVEL {:<3} {:<6} {}",
+            stats.score,
+            best.max(stats.score),
+            stats.level,
+            popup.unwrap_or_default(),
+            status
+        )),
+        MoveTo(0, map.height() as u16 + 1),
+        Clear(ClearType::CurrentLine),
+        SetForegroundColor(Color::Yellow),
+        Print("ᗧ ".repeat(stats.lives as usize)),
+        ResetColor,
+    )
+}